@@ -0,0 +1,176 @@
+use std::io::Write;
+#[cfg(feature = "arrow")]
+use std::path::Path;
+
+use tokio::runtime::{Builder, Runtime};
+
+use crate::compare::{CompareOptions, TableComparison};
+use crate::ddl::ApplyOptions;
+use crate::error::MetaError;
+use crate::ident::QualifiedName;
+#[cfg(feature = "arrow")]
+use crate::export::parquet::ParquetOptions;
+use crate::export::query::RowFormat;
+use crate::meta::MetadataService;
+use crate::modal::{ConnConfig, Metadata, Privilege, TableInfo};
+use crate::page::Page;
+use crate::profile::{ColumnProfile, ProfileOptions};
+use crate::sample::{SampleRows, SampleStrategy};
+
+/// 同步版本的 [`MetadataService`]，内部持有单线程运行时，供命令行工具、build.rs 等非异步场景使用。
+/// 不能在异步运行时的上下文中调用，否则会 panic
+#[derive(Debug)]
+pub struct MetadataServiceBlocking {
+    // 被包装的异步服务
+    service: MetadataService,
+    // 执行异步调用的运行时
+    runtime: Runtime,
+}
+
+impl MetadataServiceBlocking {
+    pub fn new(connection_config: ConnConfig) -> Result<Self, MetaError> {
+        Self::from_service(MetadataService::new(connection_config)?)
+    }
+
+    /// 包装已配置好（只读、超时、类型映射等）的服务
+    pub fn from_service(service: MetadataService) -> Result<Self, MetaError> {
+        let runtime = Builder::new_current_thread().enable_all().build()?;
+        Ok(Self { service, runtime })
+    }
+
+    /// 被包装的异步服务
+    pub fn service(&self) -> &MetadataService {
+        &self.service
+    }
+
+    pub fn get_metadata(&self) -> Result<Metadata, MetaError> {
+        self.runtime.block_on(self.service.get_metadata())
+    }
+
+    pub fn get_metadata_for_schemas(&self, schemas: &[&str]) -> Result<Metadata, MetaError> {
+        self.runtime
+            .block_on(self.service.get_metadata_for_schemas(schemas))
+    }
+
+    pub fn refresh(&self, metadata: &mut Metadata) -> Result<(), MetaError> {
+        self.runtime.block_on(self.service.refresh(metadata))
+    }
+
+    pub fn count(&self, sql: &str) -> Result<i64, MetaError> {
+        self.runtime.block_on(self.service.count(sql))
+    }
+
+    pub fn query(&self, sql: &str) -> Result<Vec<Vec<String>>, MetaError> {
+        self.runtime.block_on(self.service.query(sql))
+    }
+
+    pub fn query_page(
+        &self,
+        sql: &str,
+        page: u64,
+        page_size: u64,
+        with_total: bool,
+    ) -> Result<Page, MetaError> {
+        self.runtime
+            .block_on(self.service.query_page(sql, page, page_size, with_total))
+    }
+
+    pub fn query_page_after(
+        &self,
+        sql: &str,
+        key: &str,
+        after: Option<&str>,
+        page_size: u64,
+    ) -> Result<Page, MetaError> {
+        self.runtime
+            .block_on(self.service.query_page_after(sql, key, after, page_size))
+    }
+
+    pub fn export_query<W: Write>(
+        &self,
+        sql: &str,
+        format: RowFormat,
+        writer: W,
+    ) -> Result<u64, MetaError> {
+        self.runtime
+            .block_on(self.service.export_query(sql, format, writer))
+    }
+
+    #[cfg(feature = "arrow")]
+    pub fn export_table_to_parquet(
+        &self,
+        table: &TableInfo,
+        path: impl AsRef<Path>,
+        options: &ParquetOptions,
+    ) -> Result<u64, MetaError> {
+        self.runtime
+            .block_on(self.service.export_table_to_parquet(table, path, options))
+    }
+
+    pub fn compare_data(
+        &self,
+        target: &MetadataServiceBlocking,
+        tables: &[TableInfo],
+        options: &CompareOptions,
+    ) -> Result<Vec<Result<TableComparison, MetaError>>, MetaError> {
+        self.runtime
+            .block_on(self.service.compare_data(&target.service, tables, options))
+    }
+
+    pub fn apply_schema(
+        &self,
+        target: &ConnConfig,
+        metadata: &Metadata,
+        options: &ApplyOptions,
+    ) -> Result<Vec<String>, MetaError> {
+        self.runtime
+            .block_on(self.service.apply_schema(target, metadata, options))
+    }
+
+    pub fn sample(
+        &self,
+        table: &QualifiedName,
+        n: usize,
+        strategy: SampleStrategy,
+    ) -> Result<SampleRows, MetaError> {
+        self.runtime
+            .block_on(self.service.sample(table, n, strategy))
+    }
+
+    pub fn profile(
+        &self,
+        table: &TableInfo,
+        options: &ProfileOptions,
+    ) -> Result<Vec<ColumnProfile>, MetaError> {
+        self.runtime.block_on(self.service.profile(table, options))
+    }
+
+    pub fn list_databases(&self, include_system: bool) -> Result<Vec<String>, MetaError> {
+        self.runtime
+            .block_on(self.service.list_databases(include_system))
+    }
+
+    pub fn list_schemas(&self, include_system: bool) -> Result<Vec<String>, MetaError> {
+        self.runtime
+            .block_on(self.service.list_schemas(include_system))
+    }
+
+    pub fn get_privileges(&self) -> Result<Vec<Privilege>, MetaError> {
+        self.runtime.block_on(self.service.get_privileges())
+    }
+
+    pub fn set_table_comment(&self, table: &QualifiedName, comment: &str) -> Result<(), MetaError> {
+        self.runtime
+            .block_on(self.service.set_table_comment(table, comment))
+    }
+
+    pub fn set_column_comment(
+        &self,
+        table: &QualifiedName,
+        column: &str,
+        comment: &str,
+    ) -> Result<(), MetaError> {
+        self.runtime
+            .block_on(self.service.set_column_comment(table, column, comment))
+    }
+}
@@ -0,0 +1,303 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use clickhouse::{Client, Row};
+use serde::Deserialize;
+
+use crate::error::MetaError;
+use crate::ident::Dialect;
+use crate::meta::MetaTrait;
+use crate::modal::{
+    Column, ConnConfig, FieldTypeEnum, IndexInfo, StorageInfo, TableInfo, ViewsInfo,
+};
+
+/// ClickHouse 中视图类的表引擎
+const VIEW_ENGINES: &str = "('View', 'MaterializedView', 'LiveView', 'WindowView')";
+
+/// ClickHouse 元数据操作结构体，经由 HTTP 接口访问
+#[derive(Clone)]
+pub struct ClickHouseMeta {
+    client: Client,
+    // 当前数据库
+    database: String,
+}
+
+#[derive(Row, Deserialize)]
+struct TableRow {
+    database: String,
+    name: String,
+    comment: String,
+    engine: String,
+    partition_key: String,
+    sorting_key: String,
+    primary_key: String,
+    engine_full: String,
+}
+
+#[derive(Row, Deserialize)]
+struct ColumnRow {
+    table: String,
+    name: String,
+    type_name: String,
+    default_kind: String,
+    default_expression: String,
+    comment: String,
+    is_in_primary_key: u8,
+    numeric_scale: Option<u64>,
+    character_octet_length: Option<u64>,
+    position: u64,
+}
+
+#[derive(Row, Deserialize)]
+struct IndexRow {
+    table: String,
+    name: String,
+    index_type: String,
+    expr: String,
+    granularity: u64,
+}
+
+impl ClickHouseMeta {
+    /// 创建 ClickHouseMeta 实例，url 可以是主机名或带协议的地址，port 为 HTTP 端口（默认 8123）
+    pub fn new(conn_config: &ConnConfig) -> Result<Self, MetaError> {
+        let url =
+            if conn_config.url.starts_with("http://") || conn_config.url.starts_with("https://") {
+                format!("{}:{}", conn_config.url, conn_config.port)
+            } else {
+                format!("http://{}:{}", conn_config.url, conn_config.port)
+            };
+        let client = Client::default()
+            .with_url(url)
+            .with_user(&conn_config.username)
+            .with_password(&conn_config.password)
+            .with_database(&conn_config.database);
+        Ok(Self {
+            client,
+            database: conn_config.database.clone(),
+        })
+    }
+
+    /// 查询表或视图，`views` 决定取视图类引擎还是其余引擎
+    async fn fetch_tables(&self, views: bool) -> Result<Vec<TableRow>, MetaError> {
+        let condition = if views { "IN" } else { "NOT IN" };
+        let sql = format!(
+            "SELECT database, name, comment, engine, partition_key, sorting_key, primary_key, engine_full
+             FROM system.tables
+             WHERE database = ? AND NOT is_temporary AND engine {condition} {VIEW_ENGINES}
+             ORDER BY name"
+        );
+        let rows = self
+            .client
+            .query(&sql)
+            .bind(&self.database)
+            .fetch_all::<TableRow>()
+            .await?;
+        Ok(rows)
+    }
+
+    /// 当前数据库全部字段，按表名分组并保持定义顺序
+    async fn get_columns(&self) -> Result<HashMap<String, Vec<Column>>, MetaError> {
+        let rows = self
+            .client
+            .query(
+                "SELECT table, name, type AS type_name, default_kind, default_expression, comment,
+                        is_in_primary_key, numeric_scale, character_octet_length, position
+                 FROM system.columns
+                 WHERE database = ?
+                 ORDER BY table, position",
+            )
+            .bind(&self.database)
+            .fetch_all::<ColumnRow>()
+            .await?;
+
+        let mut column_map: HashMap<String, Vec<Column>> = HashMap::new();
+        for row in rows {
+            let column_def = match row.default_kind.as_str() {
+                "" => None,
+                "DEFAULT" => Some(row.default_expression),
+                // MATERIALIZED / ALIAS / EPHEMERAL 保留种类，便于区分
+                kind => Some(format!("{kind} {}", row.default_expression)),
+            };
+            column_map.entry(row.table).or_default().push(Column {
+                name: row.name,
+                column_type: FieldTypeEnum::clickhouse_field_type(&row.type_name),
+                is_nullable: row.type_name.starts_with("Nullable("),
+                type_name: row.type_name,
+                length: row
+                    .character_octet_length
+                    .and_then(|l| i32::try_from(l).ok())
+                    .unwrap_or(-1),
+                digit: row.numeric_scale.and_then(|s| i32::try_from(s).ok()),
+                comment: non_empty(row.comment),
+                auto_increment: None,
+                column_def,
+                is_pk: row.is_in_primary_key == 1,
+                ordinal_position: u32::try_from(row.position).unwrap_or_default(),
+                tags: Default::default(),
+            });
+        }
+        Ok(column_map)
+    }
+}
+
+#[async_trait]
+impl MetaTrait for ClickHouseMeta {
+    /// ClickHouse 以反引号引用标识符
+    fn dialect(&self) -> Dialect {
+        Dialect::MySql
+    }
+
+    /// 获取表，同时带出引擎、分区键、排序键和 TTL
+    async fn get_tables(&self) -> Result<Vec<TableInfo>, MetaError> {
+        let tables = self
+            .fetch_tables(false)
+            .await?
+            .into_iter()
+            .map(|row| {
+                let mut table = TableInfo::new(row.database, row.name, non_empty(row.comment));
+                table.storage = Some(StorageInfo {
+                    ttl: extract_ttl(&row.engine_full),
+                    engine: row.engine,
+                    partition_key: non_empty(row.partition_key),
+                    sorting_key: non_empty(row.sorting_key),
+                    primary_key: non_empty(row.primary_key),
+                });
+                table
+            })
+            .collect();
+        Ok(tables)
+    }
+
+    /// ClickHouse 的主键是表达式，可能包含多个字段，原样记入 pk_column
+    async fn set_primary_key(&self, tables: &mut Vec<TableInfo>) -> Result<(), MetaError> {
+        for table in tables {
+            if let Some(primary_key) = table.storage.as_ref().and_then(|s| s.primary_key.clone()) {
+                table.set_pk_column(primary_key);
+            }
+        }
+        Ok(())
+    }
+
+    /// 设置跳数索引（data skipping index）
+    async fn set_index_key(&self, tables: &mut Vec<TableInfo>) -> Result<(), MetaError> {
+        let rows = self
+            .client
+            .query(
+                "SELECT table, name, type AS index_type, expr, granularity
+                 FROM system.data_skipping_indices
+                 WHERE database = ?",
+            )
+            .bind(&self.database)
+            .fetch_all::<IndexRow>()
+            .await?;
+
+        let mut index_map: HashMap<String, Vec<IndexInfo>> = HashMap::new();
+        for row in rows {
+            let index_def = format!(
+                "INDEX {} {} TYPE {} GRANULARITY {}",
+                row.name, row.expr, row.index_type, row.granularity
+            );
+            index_map.entry(row.table).or_default().push(IndexInfo {
+                column_name: row.expr,
+                index_name: row.name,
+                index_def,
+                is_unique: false,
+                index_type: None,
+                sub_part: None,
+            });
+        }
+
+        for table in tables {
+            if let Some(indexes) = index_map.remove(&table.table_name) {
+                table.set_index_columns(indexes);
+            }
+        }
+        Ok(())
+    }
+
+    async fn set_columns(&self, tables: &mut Vec<TableInfo>) -> Result<(), MetaError> {
+        let mut column_map = self.get_columns().await?;
+        for table in tables {
+            if let Some(columns) = column_map.remove(&table.table_name) {
+                table.set_columns(columns);
+            }
+        }
+        Ok(())
+    }
+
+    async fn get_views(&self) -> Result<Vec<ViewsInfo>, MetaError> {
+        let views = self
+            .fetch_tables(true)
+            .await?
+            .into_iter()
+            .map(|row| ViewsInfo::new(row.database, row.name))
+            .collect();
+        Ok(views)
+    }
+
+    async fn set_view_columns(&self, views: &mut Vec<ViewsInfo>) -> Result<(), MetaError> {
+        let mut column_map = self.get_columns().await?;
+        for view in views {
+            if let Some(columns) = column_map.remove(&view.view_name) {
+                view.set_columns(columns);
+            }
+        }
+        Ok(())
+    }
+
+    async fn count(&self, sql: &str) -> Result<i64, MetaError> {
+        let rows = self.query(sql).await?;
+        rows.first()
+            .and_then(|row| row.first())
+            .and_then(|v| v.parse().ok())
+            .ok_or_else(|| MetaError::DbException(format!("计数结果无效: {sql}")))
+    }
+
+    /// 以 TabSeparated 格式取回结果，所有值按文本返回
+    async fn query(&self, sql: &str) -> Result<Vec<Vec<String>>, MetaError> {
+        // `?` 是客户端的参数占位符，原样执行的 SQL 需要转义
+        let mut cursor = self
+            .client
+            .query(&sql.replace('?', "??"))
+            .fetch_bytes("TabSeparated")?;
+        let bytes = cursor.collect().await?;
+        let text = String::from_utf8_lossy(&bytes);
+        let rows = text
+            .lines()
+            .map(|line| line.split('\t').map(unescape_tsv).collect())
+            .collect();
+        Ok(rows)
+    }
+}
+
+fn non_empty(value: String) -> Option<String> {
+    if value.is_empty() { None } else { Some(value) }
+}
+
+/// 从 engine_full 中截取 TTL 子句
+fn extract_ttl(engine_full: &str) -> Option<String> {
+    let (_, rest) = engine_full.split_once(" TTL ")?;
+    let ttl = rest.split(" SETTINGS ").next().unwrap_or(rest).trim();
+    non_empty(ttl.to_string())
+}
+
+/// 还原 TabSeparated 格式中的转义字符
+fn unescape_tsv(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('t') => result.push('\t'),
+            Some('n') => result.push('\n'),
+            Some('r') => result.push('\r'),
+            Some('0') => result.push('\0'),
+            Some(other) => result.push(other),
+            None => result.push('\\'),
+        }
+    }
+    result
+}
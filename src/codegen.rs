@@ -0,0 +1,551 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+use derive_builder::Builder;
+
+use crate::error::MetaError;
+use crate::modal::{
+    Column, DefaultValue, FieldTypeEnum, ForeignKey, Metadata, TableInfo, ViewsInfo,
+};
+
+/// Rust 关键字，作为字段名时需要加 `r#` 前缀
+const RUST_KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "dyn", "else", "enum", "extern",
+    "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub",
+    "ref", "return", "static", "struct", "trait", "true", "type", "unsafe", "use", "where",
+    "while", "abstract", "become", "box", "do", "final", "gen", "macro", "override", "priv", "try",
+    "typeof", "unsized", "virtual", "yield",
+];
+
+/// 不能写成 `r#` 原始标识符的关键字，作为字段名时加 `_` 后缀
+const NON_RAW_KEYWORDS: &[&str] = &["crate", "self", "super", "Self"];
+
+/// 生成代码的目标形式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CodegenTarget {
+    // 普通结构体
+    #[default]
+    Plain,
+    // 派生 `sqlx::FromRow` 的结构体
+    SqlxFromRow,
+    // SeaORM 实体，每张表一个模块（视图没有主键，不生成）
+    SeaOrm,
+}
+
+/// 字段命名规则
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NamingConvention {
+    // 转为 snake_case
+    #[default]
+    SnakeCase,
+    // 保留列名，列名不是合法标识符时退回 snake_case
+    Preserve,
+}
+
+/// 代码生成选项
+#[derive(Debug, Clone, Builder)]
+#[builder(default)]
+pub struct CodegenOptions {
+    // 生成目标
+    pub target: CodegenTarget,
+    // 结构体上的 derive，如 Debug、Clone、Serialize、Deserialize
+    pub derives: Vec<String>,
+    // 是否为视图生成结构体
+    pub include_views: bool,
+    // 是否把表/字段注释生成为文档注释
+    pub doc_comments: bool,
+    // 字段命名规则
+    pub naming: NamingConvention,
+    // 类型覆盖，键为 `表名.列名` 或数据库类型名（小写），值为 Rust 类型
+    pub type_overrides: HashMap<String, String>,
+    // 输出目录
+    pub output_dir: PathBuf,
+    // 按表名指定的输出路径（相对 output_dir），未指定时为 `表名.rs`
+    pub output_paths: HashMap<String, PathBuf>,
+}
+
+impl Default for CodegenOptions {
+    fn default() -> Self {
+        Self {
+            target: CodegenTarget::default(),
+            derives: ["Debug", "Clone", "Serialize", "Deserialize"]
+                .map(String::from)
+                .to_vec(),
+            include_views: true,
+            doc_comments: true,
+            naming: NamingConvention::default(),
+            type_overrides: HashMap::new(),
+            output_dir: PathBuf::from("."),
+            output_paths: HashMap::new(),
+        }
+    }
+}
+
+impl CodegenOptions {
+    /// 是否派生了 serde 的 trait
+    fn uses_serde(&self) -> bool {
+        self.derives
+            .iter()
+            .any(|d| d == "Serialize" || d == "Deserialize")
+    }
+
+    /// 文件头部的 use 语句
+    fn header(&self) -> String {
+        let mut header = String::new();
+        if self.target == CodegenTarget::SeaOrm {
+            header.push_str("use sea_orm::entity::prelude::*;\n");
+        }
+        if self.uses_serde() {
+            header.push_str("use serde::{Deserialize, Serialize};\n");
+        }
+        header
+    }
+
+    /// 目标形式需要的全部 derive
+    fn all_derives(&self) -> Vec<String> {
+        let required: &[&str] = match self.target {
+            CodegenTarget::Plain => &[],
+            CodegenTarget::SqlxFromRow => &["sqlx::FromRow"],
+            CodegenTarget::SeaOrm => &["Clone", "Debug", "PartialEq", "DeriveEntityModel"],
+        };
+        let mut derives = self.derives.clone();
+        for derive in required {
+            if !derives.iter().any(|d| d == derive) {
+                derives.push(derive.to_string());
+            }
+        }
+        derives
+    }
+
+    /// 按命名规则生成字段名
+    fn field_name(&self, column: &str) -> String {
+        let is_ident = !column.is_empty()
+            && !column.starts_with(|c: char| c.is_ascii_digit())
+            && column.chars().all(|c| c.is_alphanumeric() || c == '_');
+        match self.naming {
+            NamingConvention::Preserve if is_ident => escape_keyword(column.to_string()),
+            _ => to_field_name(column),
+        }
+    }
+
+    /// 字段的 Rust 类型，优先使用类型覆盖，可空字段包装为 Option
+    fn rust_type(&self, table: &str, column: &Column) -> String {
+        let rust_type = self
+            .type_overrides
+            .get(&format!("{table}.{}", column.name))
+            .or_else(|| self.type_overrides.get(&column.type_name.to_lowercase()))
+            .cloned()
+            .unwrap_or_else(|| match (self.target, &column.column_type) {
+                (CodegenTarget::SqlxFromRow, FieldTypeEnum::Interval) => {
+                    "sqlx::postgres::types::PgInterval".to_string()
+                }
+                (_, column_type) => field_rust_type(column_type, &column.type_name),
+            });
+        if column.is_nullable {
+            format!("Option<{rust_type}>")
+        } else {
+            rust_type
+        }
+    }
+
+    /// 表对应的输出文件路径
+    fn output_path(&self, table: &str) -> PathBuf {
+        let path = self
+            .output_paths
+            .get(table)
+            .cloned()
+            .unwrap_or_else(|| PathBuf::from(format!("{}.rs", to_snake_case(table))));
+        self.output_dir.join(path)
+    }
+}
+
+impl Metadata {
+    /// 为所有表（及视图）生成 Rust 源码，SeaORM 目标下每张表包在同名模块中，没有主键的表跳过
+    pub fn generate_rust(&self, options: &CodegenOptions) -> String {
+        let mut code = options.header();
+        for (name, body) in self.rust_items(options) {
+            code.push('\n');
+            if options.target == CodegenTarget::SeaOrm {
+                code.push_str(&format!(
+                    "pub mod {} {{\n    use super::*;\n\n",
+                    to_field_name(name)
+                ));
+                for line in body.lines() {
+                    if !line.is_empty() {
+                        code.push_str("    ");
+                    }
+                    code.push_str(line);
+                    code.push('\n');
+                }
+                code.push_str("}\n");
+            } else {
+                code.push_str(&body);
+            }
+        }
+        code
+    }
+
+    /// 按表生成文件内容，返回（输出路径，源码）
+    pub fn generate_rust_files(&self, options: &CodegenOptions) -> Vec<(PathBuf, String)> {
+        self.rust_items(options)
+            .into_iter()
+            .map(|(name, body)| {
+                (
+                    options.output_path(name),
+                    format!("{}\n{body}", options.header()),
+                )
+            })
+            .collect()
+    }
+
+    /// 按表生成文件并写入磁盘，返回写入的文件路径
+    pub fn write_rust(&self, options: &CodegenOptions) -> Result<Vec<PathBuf>, MetaError> {
+        let files = self.generate_rust_files(options);
+        let mut paths = Vec::with_capacity(files.len());
+        for (path, code) in files {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(&path, code)?;
+            paths.push(path);
+        }
+        Ok(paths)
+    }
+
+    /// 各表（及视图）的名称与代码；SeaORM 实体必须有主键，没有主键的表跳过，
+    /// 引用未生成实体的表的外键不生成关系
+    fn rust_items(&self, options: &CodegenOptions) -> Vec<(&str, String)> {
+        let generated = |t: &TableInfo| {
+            options.target != CodegenTarget::SeaOrm || t.columns.iter().any(|c| c.is_pk)
+        };
+        let tables = self
+            .tables
+            .iter()
+            .filter(|t| {
+                if !generated(t) {
+                    log::warn!("表 {} 没有主键，跳过 SeaORM 实体生成", t.table_name);
+                }
+                generated(t)
+            })
+            .map(|t| {
+                let foreign_keys: Vec<&ForeignKey> = t
+                    .foreign_keys
+                    .iter()
+                    .filter(|fk| {
+                        self.find_referenced(&fk.referenced_schema, &fk.referenced_table)
+                            .is_some_and(generated)
+                    })
+                    .collect();
+                let code = render_struct(
+                    &t.schema,
+                    &t.table_name,
+                    t.comment.as_deref(),
+                    &t.columns,
+                    &foreign_keys,
+                    options,
+                );
+                (t.table_name.as_str(), code)
+            });
+        let views = self
+            .views
+            .iter()
+            .filter(|_| options.include_views && options.target != CodegenTarget::SeaOrm)
+            .map(|v| (v.view_name.as_str(), v.generate_rust(options)));
+        tables.chain(views).collect()
+    }
+}
+
+impl TableInfo {
+    /// 生成表对应的 Rust 结构体（或 SeaORM 实体）源码，不含 use 语句。
+    /// SeaORM 实体为每个外键生成关系，假定被引用表的实体在同级的同名模块中
+    pub fn generate_rust(&self, options: &CodegenOptions) -> String {
+        let foreign_keys: Vec<&ForeignKey> = self.foreign_keys.iter().collect();
+        render_struct(
+            &self.schema,
+            &self.table_name,
+            self.comment.as_deref(),
+            &self.columns,
+            &foreign_keys,
+            options,
+        )
+    }
+}
+
+impl ViewsInfo {
+    /// 生成视图对应的 Rust 结构体源码，不含 use 语句
+    pub fn generate_rust(&self, options: &CodegenOptions) -> String {
+        render_struct(&self.schema, &self.view_name, None, &self.columns, &[], options)
+    }
+}
+
+impl DefaultValue {
+    /// 应用侧等价的 Rust 表达式，无法在应用侧生成的默认值返回 None
+    pub fn rust_expr(&self) -> Option<String> {
+        let expr = match self {
+            DefaultValue::CurrentTimestamp => "chrono::Utc::now()",
+            DefaultValue::CurrentDate => "chrono::Utc::now().date_naive()",
+            DefaultValue::CurrentTime => "chrono::Utc::now().time()",
+            DefaultValue::Uuid => "uuid::Uuid::new_v4()",
+            DefaultValue::Null => "None",
+            DefaultValue::Literal(literal)
+                if literal == "true" || literal == "false" || literal.parse::<f64>().is_ok() =>
+            {
+                return Some(literal.clone());
+            }
+            DefaultValue::Literal(literal) => return Some(format!("{literal:?}.to_string()")),
+            DefaultValue::Sequence(_) | DefaultValue::Expression(_) => return None,
+        };
+        Some(expr.to_string())
+    }
+}
+
+/// 渲染单个结构体，SeaORM 实体只为给出的外键生成关系
+fn render_struct(
+    schema: &str,
+    name: &str,
+    comment: Option<&str>,
+    columns: &[Column],
+    foreign_keys: &[&ForeignKey],
+    options: &CodegenOptions,
+) -> String {
+    let sea_orm = options.target == CodegenTarget::SeaOrm;
+    let mut code = String::new();
+    if options.doc_comments {
+        push_doc(&mut code, comment, "");
+    }
+    let derives = options.all_derives();
+    if !derives.is_empty() {
+        code.push_str(&format!("#[derive({})]\n", derives.join(", ")));
+    }
+    if sea_orm {
+        code.push_str(&format!("#[sea_orm(table_name = \"{name}\""));
+        if !schema.is_empty() {
+            code.push_str(&format!(", schema_name = \"{schema}\""));
+        }
+        code.push_str(")]\npub struct Model {\n");
+    } else {
+        code.push_str(&format!("pub struct {} {{\n", to_pascal_case(name)));
+    }
+    for column in columns {
+        if options.doc_comments {
+            push_doc(&mut code, column.comment.as_deref(), "    ");
+        }
+        let field = options.field_name(&column.name);
+        let renamed = field.trim_start_matches("r#") != column.name;
+        if renamed && options.uses_serde() {
+            code.push_str(&format!("    #[serde(rename = \"{}\")]\n", column.name));
+        }
+        if renamed && options.target == CodegenTarget::SqlxFromRow {
+            code.push_str(&format!("    #[sqlx(rename = \"{}\")]\n", column.name));
+        }
+        if sea_orm {
+            let mut attrs = Vec::new();
+            if column.is_pk {
+                attrs.push("primary_key".to_string());
+                if column.auto_increment != Some(true) {
+                    attrs.push("auto_increment = false".to_string());
+                }
+            }
+            if renamed {
+                attrs.push(format!("column_name = \"{}\"", column.name));
+            }
+            if !attrs.is_empty() {
+                code.push_str(&format!("    #[sea_orm({})]\n", attrs.join(", ")));
+            }
+        }
+        code.push_str(&format!(
+            "    pub {}: {},\n",
+            field,
+            options.rust_type(name, column)
+        ));
+    }
+    code.push_str("}\n");
+    if sea_orm {
+        code.push_str(&render_relations(name, foreign_keys, options));
+        code.push_str("\nimpl ActiveModelBehavior for ActiveModel {}\n");
+    }
+    code
+}
+
+/// SeaORM 的 `Relation` 枚举，每个外键生成一个 belongs_to 关系，被引用表只引用一次时同时实现 `Related`。
+/// 被引用方的 has_many 关系不生成。同一张表被引用多次时以外键名区分关系
+fn render_relations(table: &str, foreign_keys: &[&ForeignKey], options: &CodegenOptions) -> String {
+    let derive = "\n#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]\n";
+    if foreign_keys.is_empty() {
+        return format!("{derive}pub enum Relation {{}}\n");
+    }
+    let references = |fk: &ForeignKey| {
+        foreign_keys
+            .iter()
+            .filter(|other| other.referenced_table == fk.referenced_table)
+            .count()
+    };
+    let column_list = |prefix: &str, columns: &[String]| {
+        let columns: Vec<String> = columns
+            .iter()
+            .map(|c| {
+                let field = options.field_name(c);
+                format!("{prefix}Column::{}", to_pascal_case(field.trim_start_matches("r#")))
+            })
+            .collect();
+        match columns.as_slice() {
+            [column] => column.clone(),
+            _ => format!("({})", columns.join(", ")),
+        }
+    };
+
+    let mut code = format!("{derive}pub enum Relation {{\n");
+    let mut related = String::new();
+    for fk in foreign_keys {
+        let module = format!("super::{}", to_field_name(&fk.referenced_table));
+        let variant = match references(fk) {
+            1 => to_pascal_case(&fk.referenced_table),
+            _ => to_pascal_case(&fk.name),
+        };
+        let mut attrs = vec![
+            format!("belongs_to = \"{module}::Entity\""),
+            format!("from = \"{}\"", column_list("", &fk.columns)),
+            format!("to = \"{}\"", column_list(&format!("{module}::"), &fk.referenced_columns)),
+        ];
+        attrs.extend(referential_action("on_update", &fk.on_update));
+        attrs.extend(referential_action("on_delete", &fk.on_delete));
+        code.push_str(&format!("    #[sea_orm({})]\n    {variant},\n", attrs.join(", ")));
+        // 自引用需要 Linked，不实现 Related
+        if references(fk) == 1 && fk.referenced_table != table {
+            related.push_str(&format!(
+                "\nimpl Related<{module}::Entity> for Entity {{\n    fn to() -> RelationDef {{\n        Relation::{variant}.def()\n    }}\n}}\n"
+            ));
+        }
+    }
+    code.push_str("}\n");
+    code.push_str(&related);
+    code
+}
+
+/// 外键动作对应的 SeaORM 属性，如 `on_delete = "Cascade"`，未知的动作不生成
+fn referential_action(name: &str, action: &str) -> Option<String> {
+    let action = to_pascal_case(action);
+    ["Cascade", "SetNull", "SetDefault", "Restrict", "NoAction"]
+        .contains(&action.as_str())
+        .then(|| format!("{name} = \"{action}\""))
+}
+
+/// 追加文档注释，多行注释逐行输出
+fn push_doc(code: &mut String, comment: Option<&str>, indent: &str) {
+    let Some(comment) = comment.filter(|c| !c.trim().is_empty()) else {
+        return;
+    };
+    for line in comment.lines() {
+        code.push_str(&format!("{indent}/// {}\n", line.trim_end()));
+    }
+}
+
+/// 字段类型对应的 Rust 类型
+fn field_rust_type(column_type: &FieldTypeEnum, type_name: &str) -> String {
+    let rust_type = match column_type {
+        FieldTypeEnum::Long => "i64",
+        FieldTypeEnum::Integer => "i32",
+        FieldTypeEnum::BigInt => "u64",
+        FieldTypeEnum::Float => "f32",
+        FieldTypeEnum::Double => "f64",
+        FieldTypeEnum::Boolean => "bool",
+        FieldTypeEnum::ByteArray | FieldTypeEnum::Blob => "Vec<u8>",
+        // PostgreSQL 的日期时间类型都映射为 Date，需要按类型名细分
+        FieldTypeEnum::Date => date_rust_type(type_name),
+        FieldTypeEnum::LocalDate => "chrono::NaiveDate",
+        FieldTypeEnum::Time | FieldTypeEnum::LocalTime => "chrono::NaiveTime",
+        FieldTypeEnum::LocalDateTime => "chrono::NaiveDateTime",
+        FieldTypeEnum::Timestamp => "chrono::DateTime<chrono::Utc>",
+        FieldTypeEnum::BigDec | FieldTypeEnum::Decimal { .. } => "bigdecimal::BigDecimal",
+        FieldTypeEnum::Uuid => "uuid::Uuid",
+        FieldTypeEnum::Json => "serde_json::Value",
+        FieldTypeEnum::Array(element) => {
+            return format!(
+                "Vec<{}>",
+                field_rust_type(element, type_name.trim_start_matches('_'))
+            );
+        }
+        FieldTypeEnum::String
+        | FieldTypeEnum::Character
+        | FieldTypeEnum::Object
+        | FieldTypeEnum::Clob
+        | FieldTypeEnum::Interval
+        | FieldTypeEnum::Custom(_) => "String",
+    };
+    rust_type.to_string()
+}
+
+/// 按数据库类型名细分日期时间类型
+fn date_rust_type(type_name: &str) -> &'static str {
+    let type_name = type_name.to_lowercase();
+    if type_name.starts_with("timestamptz") || type_name.contains("with time zone") {
+        "chrono::DateTime<chrono::Utc>"
+    } else if type_name.starts_with("timestamp") || type_name == "datetime" {
+        "chrono::NaiveDateTime"
+    } else if type_name.starts_with("time") {
+        "chrono::NaiveTime"
+    } else {
+        "chrono::NaiveDate"
+    }
+}
+
+/// 列名转为合法的 Rust 字段名
+pub(crate) fn to_field_name(name: &str) -> String {
+    let mut field = to_snake_case(name);
+    if field.is_empty() || field.starts_with(|c: char| c.is_ascii_digit()) {
+        field.insert(0, '_');
+    }
+    escape_keyword(field)
+}
+
+/// Rust 关键字加 `r#` 前缀，`crate`、`self` 等加 `_` 后缀
+fn escape_keyword(mut field: String) -> String {
+    if NON_RAW_KEYWORDS.contains(&field.as_str()) {
+        field.push('_');
+    } else if RUST_KEYWORDS.contains(&field.as_str()) {
+        field.insert_str(0, "r#");
+    }
+    field
+}
+
+/// 转为 snake_case，非字母数字字符视为分隔符
+pub(crate) fn to_snake_case(name: &str) -> String {
+    let mut result = String::new();
+    let mut prev_lower = false;
+    for c in name.chars() {
+        if c.is_alphanumeric() {
+            if c.is_uppercase() && prev_lower {
+                result.push('_');
+            }
+            result.extend(c.to_lowercase());
+            prev_lower = c.is_lowercase() || c.is_ascii_digit();
+        } else {
+            if !result.is_empty() && !result.ends_with('_') {
+                result.push('_');
+            }
+            prev_lower = false;
+        }
+    }
+    result.trim_end_matches('_').to_string()
+}
+
+/// 转为 PascalCase，用作结构体名
+pub(crate) fn to_pascal_case(name: &str) -> String {
+    let pascal: String = to_snake_case(name)
+        .split('_')
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect();
+    if pascal.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("T{pascal}")
+    } else {
+        pascal
+    }
+}
@@ -0,0 +1,153 @@
+use derive_builder::Builder;
+use serde::{Deserialize, Serialize};
+
+use crate::error::MetaError;
+use crate::ident::{Dialect, QualifiedName, qualified_name, quote_ident, quote_literal};
+use crate::meta::{MetaTrait, MetadataService};
+use crate::modal::{ConnConfig, TableInfo};
+use crate::profile::text;
+
+/// 数据比对选项
+#[derive(Debug, Clone, Builder)]
+#[builder(default)]
+pub struct CompareOptions {
+    // 是否计算校验和，为 false 时只比较行数
+    pub checksum: bool,
+    // 目标库中表所在的 schema（MySQL 为数据库），为空时与源表相同
+    pub target_schema: Option<String>,
+}
+
+impl Default for CompareOptions {
+    fn default() -> Self {
+        Self {
+            checksum: true,
+            target_schema: None,
+        }
+    }
+}
+
+/// 单张表的比对结果
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TableComparison {
+    // 源表
+    pub table: QualifiedName,
+    // 源表行数
+    pub source_rows: u64,
+    // 目标表行数
+    pub target_rows: u64,
+    // 源表校验和，未计算时为 None
+    pub source_checksum: Option<String>,
+    // 目标表校验和
+    pub target_checksum: Option<String>,
+}
+
+impl TableComparison {
+    /// 行数与校验和（如有）是否都一致
+    pub fn is_match(&self) -> bool {
+        self.source_rows == self.target_rows && self.source_checksum == self.target_checksum
+    }
+}
+
+/// 比对两个连接中同名表的数据，用于校验复制或迁移结果，按 `tables` 的顺序返回各表的结果
+pub async fn compare(
+    source: ConnConfig,
+    target: ConnConfig,
+    tables: &[TableInfo],
+    options: &CompareOptions,
+) -> Result<Vec<Result<TableComparison, MetaError>>, MetaError> {
+    let source = MetadataService::new(source)?;
+    let target = MetadataService::new(target)?;
+    source.compare_data(&target, tables, options).await
+}
+
+impl MetadataService {
+    /// 与 `target` 中的同名表比对行数和校验和。校验和是每行所有字段文本的 MD5 之和，与行的顺序无关；
+    /// 字段的文本形式因数据库而异，两端方言不同时只比较行数。单张表失败（如目标表不存在）不影响其它表
+    pub async fn compare_data(
+        &self,
+        target: &MetadataService,
+        tables: &[TableInfo],
+        options: &CompareOptions,
+    ) -> Result<Vec<Result<TableComparison, MetaError>>, MetaError> {
+        let (source_dialect, target_dialect) = (self.dialect(), target.dialect());
+        let checksum = options.checksum && source_dialect == target_dialect;
+        let compare = async {
+            let (source_handler, target_handler) = futures::try_join!(
+                self.create_metadata_handler(),
+                target.create_metadata_handler()
+            )?;
+            let mut results = Vec::with_capacity(tables.len());
+            for table in tables {
+                let target_schema = options.target_schema.as_deref().unwrap_or(&table.schema);
+                let source_sql = summary_sql(table, &table.schema, source_dialect, checksum);
+                let target_sql = summary_sql(table, target_schema, target_dialect, checksum);
+                let result = futures::try_join!(
+                    summarize(source_handler.as_ref(), &source_sql),
+                    summarize(target_handler.as_ref(), &target_sql)
+                )
+                .map(
+                    |((source_rows, source_checksum), (target_rows, target_checksum))| {
+                        TableComparison {
+                            table: table.qualified_name(),
+                            source_rows,
+                            target_rows,
+                            source_checksum,
+                            target_checksum,
+                        }
+                    },
+                );
+                results.push(result);
+            }
+            Ok(results)
+        };
+        self.guard(compare, None).await
+    }
+}
+
+/// 统计行数和校验和的 SQL，结果都转换为文本
+fn summary_sql(table: &TableInfo, schema: &str, dialect: Dialect, checksum: bool) -> String {
+    let from = qualified_name(schema, &table.table_name, dialect);
+    let count = text(dialect, "COUNT(*)");
+    if !checksum || table.columns.is_empty() {
+        return format!("SELECT {count} FROM {from}");
+    }
+
+    // 空值与空串区分开
+    let null = quote_literal("\\N", dialect);
+    let fields: Vec<_> = table
+        .columns
+        .iter()
+        .map(|c| {
+            format!(
+                "COALESCE({}, {null})",
+                text(dialect, &quote_ident(&c.name, dialect))
+            )
+        })
+        .collect();
+    let row = format!("CONCAT_WS('|', {})", fields.join(", "));
+    // 取 MD5 的前 15 位十六进制（60 位）转为整数，求和不会溢出
+    let hash = match dialect {
+        Dialect::MySql => format!("CAST(CONV(SUBSTR(MD5({row}), 1, 15), 16, 10) AS UNSIGNED)"),
+        Dialect::Postgresql | Dialect::Sqlite => {
+            format!("('x' || SUBSTR(MD5({row}), 1, 15))::bit(60)::bigint")
+        }
+    };
+    let sum = text(dialect, &format!("COALESCE(SUM({hash}), 0)"));
+    format!("SELECT {count}, {sum} FROM {from}")
+}
+
+/// 执行统计 SQL，返回行数和校验和
+async fn summarize(handler: &dyn MetaTrait, sql: &str) -> Result<(u64, Option<String>), MetaError> {
+    let row = handler
+        .query(sql)
+        .await?
+        .into_iter()
+        .next()
+        .unwrap_or_default();
+    let mut values = row.into_iter();
+    let count = values.next().unwrap_or_default();
+    let count = count
+        .parse()
+        .map_err(|_| MetaError::DbException(format!("无法解析计数结果: {count}")))?;
+    Ok((count, values.next()))
+}
@@ -0,0 +1,60 @@
+use std::fmt::{self, Debug};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::error::MetaError;
+use crate::modal::ConnConfig;
+
+/// 密码提供者，在建立连接时解析密码，避免把明文密码保存在 [`ConnConfig`] 中。
+/// Vault、AWS Secrets Manager 等由调用方自行实现
+#[async_trait]
+pub trait CredentialsProvider: Send + Sync {
+    /// 返回本次连接使用的密码
+    async fn password(&self, config: &ConnConfig) -> Result<String, MetaError>;
+}
+
+/// 从环境变量读取密码
+#[derive(Debug, Clone)]
+pub struct EnvPassword(pub String);
+
+#[async_trait]
+impl CredentialsProvider for EnvPassword {
+    async fn password(&self, _config: &ConnConfig) -> Result<String, MetaError> {
+        std::env::var(&self.0)
+            .map_err(|e| MetaError::InvalidArgument(format!("读取环境变量 {} 失败: {e}", self.0)))
+    }
+}
+
+/// 从文件读取密码，去掉末尾的换行，适用于 Docker / Kubernetes 挂载的 secret
+#[derive(Debug, Clone)]
+pub struct FilePassword(pub PathBuf);
+
+#[async_trait]
+impl CredentialsProvider for FilePassword {
+    async fn password(&self, _config: &ConnConfig) -> Result<String, MetaError> {
+        let password = std::fs::read_to_string(&self.0)?;
+        Ok(password.trim_end_matches(['\r', '\n']).to_string())
+    }
+}
+
+/// 配置在 [`ConnConfig`] 上的密码提供者
+#[derive(Clone)]
+pub struct Credentials(Arc<dyn CredentialsProvider>);
+
+impl Credentials {
+    pub fn new(provider: impl CredentialsProvider + 'static) -> Self {
+        Self(Arc::new(provider))
+    }
+
+    pub(crate) async fn password(&self, config: &ConnConfig) -> Result<String, MetaError> {
+        self.0.password(config).await
+    }
+}
+
+impl Debug for Credentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Credentials")
+    }
+}
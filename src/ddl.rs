@@ -0,0 +1,332 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use derive_builder::Builder;
+
+use crate::error::MetaError;
+use crate::ident::{Dialect, qualified_name, quote_ident, quote_literal};
+use crate::meta::MetadataService;
+use crate::modal::{Column, ConnConfig, DefaultValue, IndexInfo, Metadata, TableInfo};
+
+/// 建表选项
+#[derive(Debug, Clone, Builder)]
+#[builder(default)]
+pub struct ApplyOptions {
+    // 只生成 SQL 不执行
+    pub dry_run: bool,
+    // 按目标数据库转换字段类型和默认值；为 false 时沿用原类型名和默认值表达式，只适用于同类数据库之间复制
+    pub translate_types: bool,
+    // 建表时带上 IF NOT EXISTS，目标已有同名表时跳过
+    pub if_not_exists: bool,
+    // 是否创建索引
+    pub indexes: bool,
+    // 是否带上表和字段注释
+    pub comments: bool,
+    // 建到指定的 schema（MySQL 为数据库）；为空时 PostgreSQL 沿用原 schema，MySQL 建到连接的数据库
+    pub target_schema: Option<String>,
+}
+
+impl Default for ApplyOptions {
+    fn default() -> Self {
+        Self {
+            dry_run: false,
+            translate_types: true,
+            if_not_exists: true,
+            indexes: true,
+            comments: true,
+            target_schema: None,
+        }
+    }
+}
+
+impl MetadataService {
+    /// 在 `target` 上按采集到的元数据建表，包括主键、唯一索引和普通索引，返回按顺序执行的 SQL；
+    /// `dry_run` 时只返回 SQL。外键和检查约束不会创建。
+    /// 语句逐条执行，中途失败时已执行的不会回滚；当前服务为只读模式时禁止执行
+    pub async fn apply_schema(
+        &self,
+        target: &ConnConfig,
+        metadata: &Metadata,
+        options: &ApplyOptions,
+    ) -> Result<Vec<String>, MetaError> {
+        let statements = schema_sql(metadata, Dialect::from(&target.db_type), options);
+        if options.dry_run {
+            return Ok(statements);
+        }
+        self.check_writable("建表")?;
+        let target = MetadataService::new(target.clone())?;
+        let apply = async {
+            let handler = target.create_metadata_handler().await?;
+            for sql in &statements {
+                handler.execute(sql).await?;
+            }
+            Ok(statements)
+        };
+        self.guard(apply, None).await
+    }
+}
+
+/// 生成整个元数据在 `dialect` 下的建表语句，即 [`MetadataService::apply_schema`] 执行的语句
+pub fn schema_sql(metadata: &Metadata, dialect: Dialect, options: &ApplyOptions) -> Vec<String> {
+    let schema = |table: &TableInfo| match (&options.target_schema, dialect) {
+        (Some(schema), _) => schema.clone(),
+        (None, Dialect::MySql) => String::new(),
+        (None, _) => table.schema.clone(),
+    };
+
+    let mut statements = Vec::new();
+    // PostgreSQL 的非默认 schema 需要先创建
+    if dialect == Dialect::Postgresql {
+        let schemas: BTreeSet<_> = metadata.tables.iter().map(schema).collect();
+        for name in schemas {
+            if !name.is_empty() && Some(name.as_str()) != dialect.default_schema() {
+                statements.push(format!(
+                    "CREATE SCHEMA IF NOT EXISTS {}",
+                    quote_ident(&name, dialect)
+                ));
+            }
+        }
+    }
+    // MySQL 的索引名只在表内唯一，其它数据库在 schema 内唯一，重名的索引加上表名前缀
+    let mut index_tables: BTreeMap<(String, &str), BTreeSet<&str>> = BTreeMap::new();
+    if dialect != Dialect::MySql {
+        for table in &metadata.tables {
+            for index in &table.index_columns {
+                index_tables
+                    .entry((schema(table), index.index_name.as_str()))
+                    .or_default()
+                    .insert(table.table_name.as_str());
+            }
+        }
+    }
+    for table in &metadata.tables {
+        let schema = schema(table);
+        let prefixed: BTreeSet<&str> = table
+            .index_columns
+            .iter()
+            .map(|index| index.index_name.as_str())
+            .filter(|index| {
+                index_tables
+                    .get(&(schema.clone(), *index))
+                    .is_some_and(|tables| tables.len() > 1)
+            })
+            .collect();
+        statements.extend(table_sql(table, &schema, dialect, options, &prefixed));
+    }
+    statements
+}
+
+/// 单张表的建表、注释和索引语句
+fn table_sql(
+    table: &TableInfo,
+    schema: &str,
+    dialect: Dialect,
+    options: &ApplyOptions,
+    prefixed: &BTreeSet<&str>,
+) -> Vec<String> {
+    let name = qualified_name(schema, &table.table_name, dialect);
+    let comments = options.comments && dialect != Dialect::Sqlite;
+    let mut definitions: Vec<_> = table
+        .columns
+        .iter()
+        .map(|column| column_sql(column, dialect, options))
+        .collect();
+
+    let mut pk: Vec<_> = table
+        .columns
+        .iter()
+        .filter(|c| c.is_pk)
+        .map(|c| c.name.as_str())
+        .collect();
+    if pk.is_empty() && !table.pk_column.is_empty() {
+        pk.push(&table.pk_column);
+    }
+    if !pk.is_empty() {
+        let columns: Vec<_> = pk.iter().map(|c| quote_ident(c, dialect)).collect();
+        let constraint = match dialect {
+            Dialect::Postgresql if !table.pk_name.is_empty() => {
+                format!("CONSTRAINT {} ", quote_ident(&table.pk_name, dialect))
+            }
+            _ => String::new(),
+        };
+        definitions.push(format!("{constraint}PRIMARY KEY ({})", columns.join(", ")));
+    }
+
+    let if_not_exists = if options.if_not_exists {
+        "IF NOT EXISTS "
+    } else {
+        ""
+    };
+    let mut create = format!(
+        "CREATE TABLE {if_not_exists}{name} (\n    {}\n)",
+        definitions.join(",\n    ")
+    );
+    let mut statements = Vec::new();
+    match (&table.comment, dialect) {
+        (Some(comment), Dialect::MySql) if comments && !comment.is_empty() => {
+            create.push_str(&format!(" COMMENT = {}", quote_literal(comment, dialect)));
+        }
+        (Some(comment), Dialect::Postgresql) if comments && !comment.is_empty() => {
+            statements.push(format!(
+                "COMMENT ON TABLE {name} IS {}",
+                quote_literal(comment, dialect)
+            ));
+        }
+        _ => {}
+    }
+    statements.insert(0, create);
+
+    // MySQL 的字段注释写在字段定义中
+    if comments && dialect == Dialect::Postgresql {
+        for column in &table.columns {
+            if let Some(comment) = column.comment.as_deref().filter(|c| !c.is_empty()) {
+                statements.push(format!(
+                    "COMMENT ON COLUMN {name}.{} IS {}",
+                    quote_ident(&column.name, dialect),
+                    quote_literal(comment, dialect)
+                ));
+            }
+        }
+    }
+
+    if options.indexes {
+        statements.extend(index_sql(table, &name, dialect, options, prefixed));
+    }
+    statements
+}
+
+/// 字段定义
+fn column_sql(column: &Column, dialect: Dialect, options: &ApplyOptions) -> String {
+    let column_type = match (options.translate_types, dialect) {
+        (false, _) => column.display_type(),
+        (true, Dialect::Postgresql) => column.to_pg_type(),
+        (true, Dialect::MySql) => column.to_mysql_type(),
+        (true, Dialect::Sqlite) => column.to_sqlite_type(),
+    };
+    let mut sql = format!("{} {column_type}", quote_ident(&column.name, dialect));
+    if !column.is_nullable {
+        sql.push_str(" NOT NULL");
+    }
+
+    let default = column.default_value();
+    // 序列取值的默认值改为自增，目标库中没有对应的序列
+    let auto_increment =
+        column.auto_increment == Some(true) || matches!(default, Some(DefaultValue::Sequence(_)));
+    if auto_increment {
+        match dialect {
+            Dialect::Postgresql => sql.push_str(" GENERATED BY DEFAULT AS IDENTITY"),
+            Dialect::MySql => sql.push_str(" AUTO_INCREMENT"),
+            // SQLite 的 INTEGER PRIMARY KEY 即为自增
+            Dialect::Sqlite => {}
+        }
+    } else if let Some(default) = default_sql(column, default, dialect, options) {
+        sql.push_str(&format!(" DEFAULT {default}"));
+    }
+
+    if options.comments
+        && dialect == Dialect::MySql
+        && let Some(comment) = column.comment.as_deref().filter(|c| !c.is_empty())
+    {
+        sql.push_str(&format!(" COMMENT {}", quote_literal(comment, dialect)));
+    }
+    sql
+}
+
+/// 默认值表达式；转换类型时按分类重新生成，无法移植的表达式省略
+fn default_sql(
+    column: &Column,
+    default: Option<DefaultValue>,
+    dialect: Dialect,
+    options: &ApplyOptions,
+) -> Option<String> {
+    if !options.translate_types {
+        return column.column_def.clone();
+    }
+    // MySQL 的表达式默认值需要加括号
+    let expression = |expr: &str| match dialect {
+        Dialect::MySql => format!("({expr})"),
+        Dialect::Postgresql | Dialect::Sqlite => expr.to_string(),
+    };
+    match default? {
+        DefaultValue::CurrentTimestamp => Some("CURRENT_TIMESTAMP".into()),
+        DefaultValue::CurrentDate => Some(expression("CURRENT_DATE")),
+        DefaultValue::CurrentTime => Some(expression("CURRENT_TIME")),
+        DefaultValue::Uuid => match dialect {
+            Dialect::Postgresql => Some("gen_random_uuid()".into()),
+            Dialect::MySql => Some("(UUID())".into()),
+            Dialect::Sqlite => None,
+        },
+        DefaultValue::Literal(literal)
+            if literal == "true" || literal == "false" || literal.parse::<f64>().is_ok() =>
+        {
+            Some(literal)
+        }
+        DefaultValue::Literal(literal) => Some(quote_literal(&literal, dialect)),
+        DefaultValue::Sequence(_) | DefaultValue::Null | DefaultValue::Expression(_) => None,
+    }
+}
+
+/// 索引语句，同一索引的多个字段合并；主键索引随建表创建，跳过。
+/// 表达式索引和部分索引的表达式、条件没有采集，只按字段重建。
+/// 全文索引和空间索引只在 MySQL 中重建，前缀索引的前缀长度只有 MySQL 支持，其它数据库索引整个字段。
+/// `prefixed` 中的索引与同 schema 下其它表的索引重名，改名为 `表名_索引名`
+fn index_sql(
+    table: &TableInfo,
+    name: &str,
+    dialect: Dialect,
+    options: &ApplyOptions,
+    prefixed: &BTreeSet<&str>,
+) -> Vec<String> {
+    let mut indexes: Vec<(&IndexInfo, Vec<String>)> = Vec::new();
+    for index in &table.index_columns {
+        if index.index_name == table.pk_name || index.index_name == "PRIMARY" {
+            continue;
+        }
+        if (index.is_fulltext() || index.is_spatial()) && dialect != Dialect::MySql {
+            continue;
+        }
+        let mut column = quote_ident(&index.column_name, dialect);
+        if let Some(sub_part) = index.sub_part
+            && dialect == Dialect::MySql
+        {
+            column.push_str(&format!("({sub_part})"));
+        }
+        match indexes
+            .iter_mut()
+            .find(|(i, _)| i.index_name == index.index_name)
+        {
+            Some((_, columns)) => columns.push(column),
+            None => indexes.push((index, vec![column])),
+        }
+    }
+
+    // MySQL 不支持 CREATE INDEX IF NOT EXISTS
+    let if_not_exists = if options.if_not_exists && dialect != Dialect::MySql {
+        "IF NOT EXISTS "
+    } else {
+        ""
+    };
+    indexes
+        .into_iter()
+        .map(|(index, columns)| {
+            let kind = if index.is_fulltext() {
+                "FULLTEXT "
+            } else if index.is_spatial() {
+                "SPATIAL "
+            } else if index.is_unique {
+                "UNIQUE "
+            } else {
+                ""
+            };
+            let index_name = if prefixed.contains(index.index_name.as_str()) {
+                format!("{}_{}", table.table_name, index.index_name)
+            } else {
+                index.index_name.clone()
+            };
+            format!(
+                "CREATE {kind}INDEX {if_not_exists}{} ON {name} ({})",
+                quote_ident(&index_name, dialect),
+                columns.join(", ")
+            )
+        })
+        .collect()
+}
@@ -0,0 +1,80 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::error::MetaError;
+use crate::ident::QualifiedName;
+use crate::modal::{Metadata, TableInfo};
+
+impl Metadata {
+    /// 表之间的外键依赖，键为表，值为它通过外键引用的表。
+    /// 只包含已采集到的表，引用其它 schema 中未采集的表和自引用不计入
+    pub fn dependency_graph(&self) -> BTreeMap<QualifiedName, BTreeSet<QualifiedName>> {
+        self.tables
+            .iter()
+            .map(|table| {
+                let name = table.qualified_name();
+                let dependencies = table
+                    .foreign_keys
+                    .iter()
+                    .filter_map(|fk| self.find_referenced(&fk.referenced_schema, &fk.referenced_table))
+                    .map(TableInfo::qualified_name)
+                    .filter(|referenced| *referenced != name)
+                    .collect();
+                (name, dependencies)
+            })
+            .collect()
+    }
+
+    /// 按外键依赖排序的表，被引用的表在前，适合依次建表或导入数据；清空或删除时倒序即可。
+    /// 没有依赖关系的表保持采集时的顺序。存在循环依赖时返回 [`MetaError::DependencyCycle`]，
+    /// 其中列出环上的表
+    pub fn topological_order(&self) -> Result<Vec<QualifiedName>, MetaError> {
+        let graph = self.dependency_graph();
+        let mut pending: Vec<QualifiedName> = self.tables.iter().map(TableInfo::qualified_name).collect();
+        let mut ordered = Vec::with_capacity(pending.len());
+        let mut placed = BTreeSet::new();
+        loop {
+            let (ready, rest): (Vec<_>, Vec<_>) = pending
+                .into_iter()
+                .partition(|name| graph[name].iter().all(|d| placed.contains(d)));
+            if ready.is_empty() {
+                pending = rest;
+                break;
+            }
+            placed.extend(ready.iter().cloned());
+            ordered.extend(ready);
+            pending = rest;
+        }
+        if pending.is_empty() {
+            return Ok(ordered);
+        }
+
+        // 剩下的表在环上，或依赖环上的表；只报告能沿外键回到自身的表
+        let cycle = pending
+            .into_iter()
+            .filter(|name| reachable(&graph, name).contains(name))
+            .collect();
+        Err(MetaError::DependencyCycle(cycle))
+    }
+
+    /// 按数据库的大小写规则查找被引用的表
+    pub(crate) fn find_referenced(&self, schema: &str, table: &str) -> Option<&TableInfo> {
+        self.tables
+            .iter()
+            .find(|t| self.ident_eq(&t.schema, schema) && self.ident_eq(&t.table_name, table))
+    }
+}
+
+/// 从 `start` 出发沿外键能到达的表，不含起点本身（除非它在环上）
+fn reachable(
+    graph: &BTreeMap<QualifiedName, BTreeSet<QualifiedName>>,
+    start: &QualifiedName,
+) -> BTreeSet<QualifiedName> {
+    let mut visited = BTreeSet::new();
+    let mut stack: Vec<&QualifiedName> = graph[start].iter().collect();
+    while let Some(name) = stack.pop() {
+        if visited.insert(name.clone()) {
+            stack.extend(&graph[name]);
+        }
+    }
+    visited
+}
@@ -0,0 +1,313 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use duckdb::{Connection, params};
+use sqlparser::ast::{Expr, Statement};
+use sqlparser::dialect::DuckDbDialect;
+use sqlparser::parser::Parser;
+
+use crate::error::MetaError;
+use crate::meta::MetaTrait;
+use crate::modal::{Column, ConnConfig, FieldTypeEnum, IndexInfo, TableInfo, ViewsInfo};
+
+/// DuckDB 元数据操作结构体，读取本地 `.duckdb` 文件
+#[derive(Clone)]
+pub struct DuckDbMeta {
+    // DuckDB 连接不能跨线程共享，查询时加锁并在阻塞线程中执行
+    conn: Arc<Mutex<Connection>>,
+    // 采集的 schema，默认为 main
+    schema: String,
+}
+
+impl DuckDbMeta {
+    /// 打开 database 指定的文件，`:memory:` 表示内存数据库
+    pub fn new(conn_config: &ConnConfig) -> Result<Self, MetaError> {
+        let conn = if conn_config.database == ":memory:" {
+            Connection::open_in_memory()?
+        } else {
+            Connection::open(&conn_config.database)?
+        };
+        Ok(Self {
+            conn: Arc::new(Mutex::new(conn)),
+            schema: conn_config
+                .schema
+                .clone()
+                .unwrap_or_else(|| "main".to_string()),
+        })
+    }
+
+    /// 在阻塞线程中使用连接，避免占用异步运行时
+    async fn run<T, F>(&self, f: F) -> Result<T, MetaError>
+    where
+        T: Send + 'static,
+        F: FnOnce(&Connection, &str) -> duckdb::Result<T> + Send + 'static,
+    {
+        let conn = self.conn.clone();
+        let schema = self.schema.clone();
+        let result = tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap_or_else(|e| e.into_inner());
+            f(&conn, &schema)
+        })
+        .await
+        .map_err(|e| MetaError::DbException(format!("DuckDB查询线程异常: {e}")))?;
+        Ok(result?)
+    }
+
+    /// 字段按表名分组，`sql` 的参数为 schema，结果列依次为
+    /// 表名、字段名、类型、可空、默认值、注释、小数位、字符长度、位置
+    async fn get_columns(
+        &self,
+        sql: &'static str,
+    ) -> Result<HashMap<String, Vec<Column>>, MetaError> {
+        let rows = self
+            .run(move |conn, schema| {
+                let mut stmt = conn.prepare(sql)?;
+                let rows = stmt.query_map(params![schema], |row| {
+                    let type_name: String = row.get(2)?;
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        Column {
+                            name: row.get(1)?,
+                            column_type: FieldTypeEnum::duckdb_field_type(&type_name),
+                            type_name,
+                            length: row.get::<_, Option<i32>>(7)?.unwrap_or(-1),
+                            digit: row.get(6)?,
+                            is_nullable: row.get(3)?,
+                            comment: row.get::<_, Option<String>>(5)?.filter(|c| !c.is_empty()),
+                            auto_increment: None,
+                            column_def: row.get(4)?,
+                            is_pk: false,
+                            ordinal_position: row.get::<_, Option<u32>>(8)?.unwrap_or_default(),
+                            tags: Default::default(),
+                        },
+                    ))
+                })?;
+                rows.collect::<duckdb::Result<Vec<_>>>()
+            })
+            .await?;
+
+        let mut column_map: HashMap<String, Vec<Column>> = HashMap::new();
+        for (table, column) in rows {
+            column_map.entry(table).or_default().push(column);
+        }
+        Ok(column_map)
+    }
+}
+
+#[async_trait]
+impl MetaTrait for DuckDbMeta {
+    async fn get_tables(&self) -> Result<Vec<TableInfo>, MetaError> {
+        self.run(|conn, schema| {
+            let mut stmt = conn.prepare(
+                "SELECT schema_name, table_name, comment
+                 FROM duckdb_tables()
+                 WHERE schema_name = ? AND NOT internal AND NOT temporary
+                 ORDER BY table_name",
+            )?;
+            let rows = stmt.query_map(params![schema], |row| {
+                Ok(TableInfo::new(
+                    row.get(0)?,
+                    row.get(1)?,
+                    row.get::<_, Option<String>>(2)?.filter(|c| !c.is_empty()),
+                ))
+            })?;
+            rows.collect()
+        })
+        .await
+    }
+
+    /// 主键和唯一约束来自 duckdb_constraints()，唯一约束作为唯一索引记录
+    async fn set_primary_key(&self, tables: &mut Vec<TableInfo>) -> Result<(), MetaError> {
+        let rows = self
+            .run(|conn, schema| {
+                let mut stmt = conn.prepare(
+                    "SELECT table_name, constraint_type, constraint_index,
+                            array_to_string(constraint_column_names, ',')
+                     FROM duckdb_constraints()
+                     WHERE schema_name = ? AND constraint_type IN ('PRIMARY KEY', 'UNIQUE')",
+                )?;
+                let rows = stmt.query_map(params![schema], |row| {
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        row.get::<_, String>(1)?,
+                        row.get::<_, i64>(2)?,
+                        row.get::<_, String>(3)?,
+                    ))
+                })?;
+                rows.collect::<duckdb::Result<Vec<_>>>()
+            })
+            .await?;
+
+        for table in tables.iter_mut() {
+            for (_, constraint_type, index, columns) in
+                rows.iter().filter(|r| r.0 == table.table_name)
+            {
+                if constraint_type == "PRIMARY KEY" {
+                    table.set_pk_column(columns.clone());
+                    continue;
+                }
+                let index_name = format!("{}_unique_{index}", table.table_name);
+                table
+                    .index_columns
+                    .extend(columns.split(',').map(|column| IndexInfo {
+                        column_name: column.to_string(),
+                        index_name: index_name.clone(),
+                        index_def: String::new(),
+                        is_unique: true,
+                        index_type: None,
+                        sub_part: None,
+                    }));
+            }
+        }
+        Ok(())
+    }
+
+    /// 显式创建的索引（CREATE INDEX）。`expressions` 列在不同版本中分别为 NULL、
+    /// `[a, b]` 字符串和 VARCHAR[]，字段改从 `sql` 中的建索引语句解析
+    async fn set_index_key(&self, tables: &mut Vec<TableInfo>) -> Result<(), MetaError> {
+        let rows = self
+            .run(|conn, schema| {
+                let mut stmt = conn.prepare(
+                    "SELECT table_name, index_name, is_unique, sql
+                     FROM duckdb_indexes()
+                     WHERE schema_name = ?",
+                )?;
+                let rows = stmt.query_map(params![schema], |row| {
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        row.get::<_, String>(1)?,
+                        row.get::<_, bool>(2)?,
+                        row.get::<_, Option<String>>(3)?.unwrap_or_default(),
+                    ))
+                })?;
+                rows.collect::<duckdb::Result<Vec<_>>>()
+            })
+            .await?;
+
+        for (table_name, index_name, is_unique, index_def) in rows {
+            let Some(table) = tables.iter_mut().find(|t| t.table_name == table_name) else {
+                continue;
+            };
+            let Some(columns) = index_columns(&index_def) else {
+                log::debug!("跳过无法按字段还原的索引 {table_name}.{index_name}: {index_def}");
+                continue;
+            };
+            table
+                .index_columns
+                .extend(columns.into_iter().map(|column| IndexInfo {
+                    column_name: column,
+                    index_name: index_name.clone(),
+                    index_def: index_def.clone(),
+                    is_unique,
+                    index_type: None,
+                    sub_part: None,
+                }));
+        }
+        Ok(())
+    }
+
+    async fn set_columns(&self, tables: &mut Vec<TableInfo>) -> Result<(), MetaError> {
+        let mut column_map = self
+            .get_columns(
+                "SELECT table_name, column_name, data_type, is_nullable, column_default, comment,
+                        numeric_scale, character_maximum_length, column_index
+                 FROM duckdb_columns()
+                 WHERE schema_name = ? AND NOT internal
+                 ORDER BY table_name, column_index",
+            )
+            .await?;
+
+        for table in tables {
+            if let Some(mut columns) = column_map.remove(&table.table_name) {
+                let pk_columns: Vec<&str> = table.pk_column.split(',').collect();
+                columns
+                    .iter_mut()
+                    .for_each(|c| c.is_pk = pk_columns.contains(&c.name.as_str()));
+                table.set_columns(columns);
+            }
+        }
+        Ok(())
+    }
+
+    async fn get_views(&self) -> Result<Vec<ViewsInfo>, MetaError> {
+        self.run(|conn, schema| {
+            let mut stmt = conn.prepare(
+                "SELECT schema_name, view_name
+                 FROM duckdb_views()
+                 WHERE schema_name = ? AND NOT internal AND NOT temporary
+                 ORDER BY view_name",
+            )?;
+            let rows = stmt.query_map(params![schema], |row| {
+                Ok(ViewsInfo::new(row.get(0)?, row.get(1)?))
+            })?;
+            rows.collect()
+        })
+        .await
+    }
+
+    /// 视图字段从 information_schema 读取，该视图同时包含表和视图的字段
+    async fn set_view_columns(&self, views: &mut Vec<ViewsInfo>) -> Result<(), MetaError> {
+        let mut column_map = self
+            .get_columns(
+                "SELECT table_name, column_name, data_type, is_nullable = 'YES', column_default,
+                        NULL::VARCHAR, numeric_scale, character_maximum_length, ordinal_position
+                 FROM information_schema.columns
+                 WHERE table_schema = ?
+                 ORDER BY table_name, ordinal_position",
+            )
+            .await?;
+
+        for view in views {
+            if let Some(columns) = column_map.remove(&view.view_name) {
+                view.set_columns(columns);
+            }
+        }
+        Ok(())
+    }
+
+    async fn count(&self, sql: &str) -> Result<i64, MetaError> {
+        let sql = sql.to_string();
+        self.run(move |conn, _| conn.query_row(&sql, [], |row| row.get(0)))
+            .await
+    }
+
+    /// 借助 `COLUMNS(*)::VARCHAR` 将每一列转为文本，NULL 返回空字符串
+    async fn query(&self, sql: &str) -> Result<Vec<Vec<String>>, MetaError> {
+        let sql = format!(
+            "SELECT COLUMNS(*)::VARCHAR FROM ({}) AS q",
+            sql.trim().trim_end_matches(';')
+        );
+        self.run(move |conn, _| {
+            let mut stmt = conn.prepare(&sql)?;
+            let mut rows = stmt.query([])?;
+            let column_count = rows.as_ref().map(|s| s.column_count()).unwrap_or(0);
+            let mut result = Vec::new();
+            while let Some(row) = rows.next()? {
+                let values = (0..column_count)
+                    .map(|i| Ok(row.get::<_, Option<String>>(i)?.unwrap_or_default()))
+                    .collect::<duckdb::Result<Vec<_>>>()?;
+                result.push(values);
+            }
+            Ok(result)
+        })
+        .await
+    }
+}
+
+/// 从建索引语句中解析索引字段；语句无法解析或含表达式时返回 None
+pub(crate) fn index_columns(index_def: &str) -> Option<Vec<String>> {
+    let statements = Parser::parse_sql(&DuckDbDialect {}, index_def).ok()?;
+    let Some(Statement::CreateIndex(index)) = statements.into_iter().next() else {
+        return None;
+    };
+    index
+        .columns
+        .iter()
+        .map(|column| match &column.expr {
+            Expr::Identifier(ident) => Some(ident.value.clone()),
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>()
+        .filter(|columns| !columns.is_empty())
+}
@@ -2,8 +2,15 @@
 
 use derive_more::Display;
 use log::error;
+#[cfg(feature = "mysql")]
+use sqlx::mysql::MySqlDatabaseError;
 use thiserror::Error;
 
+use crate::ident::QualifiedName;
+
+/// 保留原始错误，供调用方通过 `source()` 取得驱动层的详细信息
+pub type BoxError = Box<dyn std::error::Error + Send + Sync + 'static>;
+
 #[derive(Debug, Display, Error)]
 pub enum MetaError {
     #[display("{_0}")]
@@ -14,15 +21,101 @@ pub enum MetaError {
 
     #[display("参数错误: {_0}")]
     InvalidArgument(String),
+
+    #[display("只读模式禁止执行: {_0}")]
+    ReadOnlyViolation(String),
+
+    #[display("数据库中没有任何表或视图: {_0}")]
+    EmptySchema(String),
+
+    #[display("连接数据库失败: {_0}")]
+    ConnectionFailed(#[source] BoxError),
+
+    #[display("认证失败: {_0}")]
+    AuthenticationFailed(#[source] BoxError),
+
+    #[display("数据库不存在: {_0}")]
+    DatabaseNotFound(#[source] BoxError),
+
+    #[display("权限不足: {_0}")]
+    PermissionDenied(#[source] BoxError),
+
+    #[display("查询超时: {_0}")]
+    QueryTimeout(#[source] BoxError),
+
+    #[display("连接池已耗尽: {_0}")]
+    PoolExhausted(#[source] BoxError),
+
+    #[display("操作已取消")]
+    Cancelled,
+
+    #[display("表之间存在循环外键依赖: {}", _0.iter().map(ToString::to_string).collect::<Vec<_>>().join(", "))]
+    DependencyCycle(Vec<QualifiedName>),
+}
+
+/// 错误类别，由 sqlx 错误类型和数据库错误码决定
+enum Kind {
+    Connection,
+    Authentication,
+    DatabaseNotFound,
+    Permission,
+    Timeout,
+    PoolExhausted,
+    Other,
+}
+
+impl Kind {
+    fn of(error: &sqlx::Error) -> Self {
+        match error {
+            sqlx::Error::Io(_) | sqlx::Error::Tls(_) | sqlx::Error::PoolClosed => Kind::Connection,
+            sqlx::Error::PoolTimedOut => Kind::PoolExhausted,
+            #[cfg(feature = "mysql")]
+            sqlx::Error::Database(e) if e.try_downcast_ref::<MySqlDatabaseError>().is_some() => {
+                Self::of_mysql(e.downcast_ref::<MySqlDatabaseError>().number())
+            }
+            sqlx::Error::Database(e) => Self::of_sqlstate(e.code().as_deref().unwrap_or_default()),
+            _ => Kind::Other,
+        }
+    }
+
+    /// PostgreSQL 的 SQLSTATE
+    fn of_sqlstate(code: &str) -> Self {
+        match code {
+            "28000" | "28P01" => Kind::Authentication,
+            "3D000" => Kind::DatabaseNotFound,
+            "42501" => Kind::Permission,
+            // query_canceled（statement_timeout）和 lock_not_available（lock_timeout）
+            "57014" | "55P03" => Kind::Timeout,
+            code if code.starts_with("08") || code == "53300" => Kind::Connection,
+            _ => Kind::Other,
+        }
+    }
+
+    /// MySQL 的错误号
+    fn of_mysql(number: u16) -> Self {
+        match number {
+            1045 => Kind::Authentication,
+            1049 => Kind::DatabaseNotFound,
+            1044 | 1142 | 1143 | 1227 => Kind::Permission,
+            // max_execution_time 超时和锁等待超时
+            3024 | 1205 => Kind::Timeout,
+            1040 => Kind::Connection,
+            _ => Kind::Other,
+        }
+    }
 }
 
 impl From<sqlx::Error> for MetaError {
     fn from(value: sqlx::Error) -> Self {
-        match value {
-            e => {
-                error!("{:?}", e);
-                MetaError::DbException(format!("{}", e))
-            }
+        error!("{:?}", value);
+        match Kind::of(&value) {
+            Kind::Connection => MetaError::ConnectionFailed(value.into()),
+            Kind::Authentication => MetaError::AuthenticationFailed(value.into()),
+            Kind::DatabaseNotFound => MetaError::DatabaseNotFound(value.into()),
+            Kind::Permission => MetaError::PermissionDenied(value.into()),
+            Kind::Timeout => MetaError::QueryTimeout(value.into()),
+            Kind::PoolExhausted => MetaError::PoolExhausted(value.into()),
+            Kind::Other => MetaError::DbException(format!("{}", value)),
         }
     }
 }
@@ -37,3 +130,35 @@ impl From<std::io::Error> for MetaError {
         }
     }
 }
+
+#[cfg(feature = "clickhouse")]
+impl From<clickhouse::error::Error> for MetaError {
+    fn from(value: clickhouse::error::Error) -> Self {
+        error!("{:?}", value);
+        MetaError::DbException(format!("{}", value))
+    }
+}
+
+#[cfg(feature = "duckdb")]
+impl From<duckdb::Error> for MetaError {
+    fn from(value: duckdb::Error) -> Self {
+        error!("{:?}", value);
+        MetaError::DbException(format!("{}", value))
+    }
+}
+
+#[cfg(feature = "arrow")]
+impl From<arrow::error::ArrowError> for MetaError {
+    fn from(value: arrow::error::ArrowError) -> Self {
+        error!("{:?}", value);
+        MetaError::BadRequest(format!("{}", value))
+    }
+}
+
+#[cfg(feature = "arrow")]
+impl From<parquet::errors::ParquetError> for MetaError {
+    fn from(value: parquet::errors::ParquetError) -> Self {
+        error!("{:?}", value);
+        MetaError::BadRequest(format!("{}", value))
+    }
+}
@@ -0,0 +1,196 @@
+use std::collections::BTreeMap;
+
+use super::sha256_hex;
+use crate::ident::QualifiedName;
+use crate::lineage::ColumnRef;
+use crate::modal::{
+    Column, DefaultValue, ForeignKey, ForeignServer, ForeignTable, IndexInfo, Metadata,
+    StorageInfo, TableInfo, ViewsInfo,
+};
+
+impl Metadata {
+    /// 生成匿名化的元数据副本，用于在问题反馈中分享结构而不暴露业务细节。
+    ///
+    /// 表、视图、字段、索引和 schema 名替换为加盐哈希（同一名称在各处得到相同结果，
+    /// 跨表的同名字段仍可对应），注释和索引定义被清除，只保留不含业务信息的函数类默认值。
+    /// 字段类型保持不变
+    pub fn anonymize(&self, salt: &str) -> Metadata {
+        let anonymizer = Anonymizer {
+            salt,
+            case_insensitive: self.case_insensitive,
+        };
+        Metadata {
+            tables: self.tables.iter().map(|t| anonymizer.table(t)).collect(),
+            views: self.views.iter().map(|v| anonymizer.view(v)).collect(),
+            case_insensitive: self.case_insensitive,
+            foreign_tables: self
+                .foreign_tables
+                .iter()
+                .map(|t| anonymizer.foreign_table(t))
+                .collect(),
+            foreign_servers: self
+                .foreign_servers
+                .iter()
+                .map(|s| anonymizer.foreign_server(s))
+                .collect(),
+            // 提示信息中含库名，不保留
+            warnings: Vec::new(),
+        }
+    }
+}
+
+struct Anonymizer<'a> {
+    // 哈希盐，相同的盐得到相同的化名
+    salt: &'a str,
+    // 不区分大小写时，仅大小写不同的名称得到相同的化名
+    case_insensitive: bool,
+}
+
+impl Anonymizer<'_> {
+    /// 名称的化名，形如 `t_1a2b3c4d5e6f`，空名称保持为空
+    fn pseudonym(&self, prefix: &str, name: &str) -> String {
+        if name.is_empty() {
+            return String::new();
+        }
+        let name = if self.case_insensitive {
+            name.to_lowercase()
+        } else {
+            name.to_string()
+        };
+        let hash = sha256_hex(format!("{}\0{prefix}\0{name}", self.salt).as_bytes());
+        format!("{prefix}_{}", &hash[..12])
+    }
+
+    fn table(&self, table: &TableInfo) -> TableInfo {
+        TableInfo {
+            schema: self.pseudonym("s", &table.schema),
+            table_name: self.pseudonym("t", &table.table_name),
+            comment: None,
+            pk_name: self.pseudonym("pk", &table.pk_name),
+            pk_column: self.pseudonym("c", &table.pk_column),
+            index_columns: table.index_columns.iter().map(|i| self.index(i)).collect(),
+            foreign_keys: table.foreign_keys.iter().map(|f| self.foreign_key(f)).collect(),
+            columns: table.columns.iter().map(|c| self.column(c)).collect(),
+            // 分区键、排序键等表达式含字段名，只保留引擎
+            storage: table.storage.as_ref().map(|s| StorageInfo {
+                engine: s.engine.clone(),
+                ..Default::default()
+            }),
+            owner: table.owner.as_ref().map(|o| self.pseudonym("u", o)),
+            // 策略表达式含字段名和业务取值，只保留是否启用
+            row_security: table.row_security,
+            policies: vec![],
+            change_marker: table.change_marker.clone(),
+            // 标签来自注释
+            tags: BTreeMap::new(),
+            created_at: table.created_at.clone(),
+            updated_at: table.updated_at.clone(),
+            // 建表语句含全部名称和注释
+            create_sql: None,
+        }
+    }
+
+    fn foreign_key(&self, fk: &ForeignKey) -> ForeignKey {
+        ForeignKey {
+            name: self.pseudonym("fk", &fk.name),
+            columns: fk.columns.iter().map(|c| self.pseudonym("c", c)).collect(),
+            referenced_schema: self.pseudonym("s", &fk.referenced_schema),
+            referenced_table: self.pseudonym("t", &fk.referenced_table),
+            referenced_columns: fk
+                .referenced_columns
+                .iter()
+                .map(|c| self.pseudonym("c", c))
+                .collect(),
+            on_update: fk.on_update.clone(),
+            on_delete: fk.on_delete.clone(),
+        }
+    }
+
+    fn view(&self, view: &ViewsInfo) -> ViewsInfo {
+        ViewsInfo {
+            schema: self.pseudonym("s", &view.schema),
+            view_name: self.pseudonym("t", &view.view_name),
+            columns: view.columns.iter().map(|c| self.column(c)).collect(),
+            create_sql: None,
+            // 查询语句含全部名称和取值，血缘只保留对应关系
+            definition: None,
+            column_lineage: view
+                .column_lineage
+                .iter()
+                .map(|(column, sources)| {
+                    let sources = sources.iter().map(|s| self.column_ref(s)).collect();
+                    (self.pseudonym("c", column), sources)
+                })
+                .collect(),
+        }
+    }
+
+    // 外部表和服务器的选项含远端地址、库表名，全部清除
+    fn foreign_table(&self, table: &ForeignTable) -> ForeignTable {
+        ForeignTable {
+            schema: self.pseudonym("s", &table.schema),
+            table_name: self.pseudonym("t", &table.table_name),
+            comment: None,
+            server: self.pseudonym("srv", &table.server),
+            options: BTreeMap::new(),
+            columns: table.columns.iter().map(|c| self.column(c)).collect(),
+        }
+    }
+
+    fn foreign_server(&self, server: &ForeignServer) -> ForeignServer {
+        ForeignServer {
+            name: self.pseudonym("srv", &server.name),
+            wrapper: server.wrapper.clone(),
+            server_type: None,
+            version: None,
+            options: BTreeMap::new(),
+            user_mappings: server
+                .user_mappings
+                .iter()
+                .map(|u| self.pseudonym("u", u))
+                .collect(),
+        }
+    }
+
+    fn column_ref(&self, column: &ColumnRef) -> ColumnRef {
+        ColumnRef {
+            table: QualifiedName::new(
+                self.pseudonym("s", &column.table.schema),
+                self.pseudonym("t", &column.table.name),
+            ),
+            column: self.pseudonym("c", &column.column),
+        }
+    }
+
+    fn index(&self, index: &IndexInfo) -> IndexInfo {
+        IndexInfo {
+            column_name: self.pseudonym("c", &index.column_name),
+            index_name: self.pseudonym("i", &index.index_name),
+            index_def: String::new(),
+            is_unique: index.is_unique,
+            index_type: index.index_type.clone(),
+            sub_part: index.sub_part,
+        }
+    }
+
+    fn column(&self, column: &Column) -> Column {
+        // 字面量、序列名和表达式可能含业务信息，只保留函数类默认值
+        let column_def = match column.default_value() {
+            Some(
+                DefaultValue::CurrentTimestamp
+                | DefaultValue::CurrentDate
+                | DefaultValue::CurrentTime
+                | DefaultValue::Uuid
+                | DefaultValue::Null,
+            ) => column.column_def.clone(),
+            _ => None,
+        };
+        Column {
+            name: self.pseudonym("c", &column.name),
+            comment: None,
+            column_def,
+            tags: BTreeMap::new(),
+            ..column.clone()
+        }
+    }
+}
@@ -0,0 +1,162 @@
+use std::collections::HashMap;
+
+use derive_builder::Builder;
+use serde_json::{Map, Value, json};
+
+use super::{schema_field_name, schema_type_name};
+use crate::modal::{Column, FieldTypeEnum, Metadata, TableInfo, ViewsInfo};
+
+/// Avro schema 生成选项
+#[derive(Debug, Clone, Default, Builder)]
+#[builder(default)]
+pub struct AvroOptions {
+    // record 的 namespace，为空时不输出
+    pub namespace: String,
+    // 是否为视图生成 schema
+    pub include_views: bool,
+    // 类型覆盖，键为 `表名.列名` 或数据库类型名（小写），值为 Avro 类型：
+    // 可以是 JSON（如 `{"type":"long","logicalType":"timestamp-millis"}`），也可以是原始类型名（如 `string`）
+    pub type_overrides: HashMap<String, String>,
+}
+
+impl AvroOptions {
+    /// 字段的 Avro 类型，优先使用类型覆盖，可空字段包装为 `["null", T]`
+    fn avro_type(&self, table: &str, column: &Column) -> Value {
+        let avro_type = self
+            .type_overrides
+            .get(&format!("{table}.{}", column.name))
+            .or_else(|| self.type_overrides.get(&column.type_name.to_lowercase()))
+            .map(|t| serde_json::from_str(t).unwrap_or_else(|_| Value::String(t.clone())))
+            .unwrap_or_else(|| field_avro_type(&column.column_type, &column.type_name));
+        if column.is_nullable {
+            json!(["null", avro_type])
+        } else {
+            avro_type
+        }
+    }
+}
+
+impl Metadata {
+    /// 为所有表（及视图）生成 Avro schema，返回 `(表名, .avsc 内容)`
+    pub fn to_avro(&self, options: &AvroOptions) -> Vec<(String, String)> {
+        let tables = self
+            .tables
+            .iter()
+            .map(|t| (t.table_name.clone(), t.to_avro(options)));
+        let views = self
+            .views
+            .iter()
+            .filter(|_| options.include_views)
+            .map(|v| (v.view_name.clone(), v.to_avro(options)));
+        tables.chain(views).collect()
+    }
+}
+
+impl TableInfo {
+    /// 生成表对应的 Avro record schema（JSON）
+    pub fn to_avro(&self, options: &AvroOptions) -> String {
+        render_record(
+            &self.table_name,
+            self.comment.as_deref(),
+            &self.columns,
+            options,
+        )
+    }
+}
+
+impl ViewsInfo {
+    /// 生成视图对应的 Avro record schema（JSON）
+    pub fn to_avro(&self, options: &AvroOptions) -> String {
+        render_record(&self.view_name, None, &self.columns, options)
+    }
+}
+
+fn render_record(
+    name: &str,
+    comment: Option<&str>,
+    columns: &[Column],
+    options: &AvroOptions,
+) -> String {
+    let fields: Vec<Value> = columns
+        .iter()
+        .map(|column| {
+            let mut field = Map::new();
+            field.insert("name".into(), schema_field_name(&column.name).into());
+            field.insert("type".into(), options.avro_type(name, column));
+            if column.is_nullable {
+                field.insert("default".into(), Value::Null);
+            }
+            if let Some(doc) = column.comment.as_deref().filter(|c| !c.is_empty()) {
+                field.insert("doc".into(), doc.into());
+            }
+            Value::Object(field)
+        })
+        .collect();
+
+    let mut record = Map::new();
+    record.insert("type".into(), "record".into());
+    record.insert("name".into(), schema_type_name(name).into());
+    if !options.namespace.is_empty() {
+        record.insert("namespace".into(), options.namespace.clone().into());
+    }
+    if let Some(doc) = comment.filter(|c| !c.is_empty()) {
+        record.insert("doc".into(), doc.into());
+    }
+    record.insert("fields".into(), fields.into());
+    // Map 与 Value 的序列化不会失败
+    serde_json::to_string_pretty(&Value::Object(record)).unwrap_or_default()
+}
+
+/// 字段类型对应的 Avro 类型，日期时间与定点数使用逻辑类型
+fn field_avro_type(column_type: &FieldTypeEnum, type_name: &str) -> Value {
+    let logical = |base: &str, logical: &str| json!({"type": base, "logicalType": logical});
+    match column_type {
+        FieldTypeEnum::Integer => "int".into(),
+        FieldTypeEnum::Long | FieldTypeEnum::BigInt => "long".into(),
+        FieldTypeEnum::Float => "float".into(),
+        FieldTypeEnum::Double => "double".into(),
+        FieldTypeEnum::Boolean => "boolean".into(),
+        FieldTypeEnum::ByteArray | FieldTypeEnum::Blob => "bytes".into(),
+        // PostgreSQL 的日期时间类型都映射为 Date，需要按类型名细分
+        FieldTypeEnum::Date => {
+            let type_name = type_name.to_lowercase();
+            if type_name.starts_with("timestamptz") || type_name.contains("with time zone") {
+                logical("long", "timestamp-micros")
+            } else if type_name.starts_with("timestamp") || type_name == "datetime" {
+                logical("long", "local-timestamp-micros")
+            } else if type_name.starts_with("time") {
+                logical("long", "time-micros")
+            } else {
+                logical("int", "date")
+            }
+        }
+        FieldTypeEnum::LocalDate => logical("int", "date"),
+        FieldTypeEnum::Time | FieldTypeEnum::LocalTime => logical("long", "time-micros"),
+        FieldTypeEnum::LocalDateTime => logical("long", "local-timestamp-micros"),
+        FieldTypeEnum::Timestamp => logical("long", "timestamp-micros"),
+        FieldTypeEnum::Decimal {
+            precision: Some(precision),
+            scale,
+        } => json!({
+            "type": "bytes",
+            "logicalType": "decimal",
+            "precision": precision,
+            "scale": scale.unwrap_or(0),
+        }),
+        FieldTypeEnum::Uuid => logical("string", "uuid"),
+        FieldTypeEnum::Array(element) => json!({
+            "type": "array",
+            "items": field_avro_type(element, type_name.trim_start_matches('_')),
+        }),
+        // 未声明精度的定点数无法使用 decimal 逻辑类型，以字符串保留原值
+        FieldTypeEnum::BigDec
+        | FieldTypeEnum::Decimal { .. }
+        | FieldTypeEnum::String
+        | FieldTypeEnum::Character
+        | FieldTypeEnum::Object
+        | FieldTypeEnum::Clob
+        | FieldTypeEnum::Json
+        | FieldTypeEnum::Interval
+        | FieldTypeEnum::Custom(_) => "string".into(),
+    }
+}
@@ -0,0 +1,208 @@
+use crate::export::dictionary::html_escape;
+use crate::modal::{Column, ForeignKey, Metadata, TableInfo};
+
+/// ER 图的文本格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiagramFormat {
+    // Mermaid `erDiagram`
+    Mermaid,
+    // Graphviz DOT
+    Dot,
+    // PlantUML 实体图
+    PlantUml,
+}
+
+impl Metadata {
+    /// 按指定格式生成 ER 图文本，包含表以及由外键得出的关系；引用图外表的外键不输出
+    pub fn to_diagram(&self, format: DiagramFormat) -> String {
+        match format {
+            DiagramFormat::Mermaid => self.to_mermaid(),
+            DiagramFormat::Dot => self.to_dot(),
+            DiagramFormat::PlantUml => self.to_plantuml(),
+        }
+    }
+
+    /// 生成 Mermaid `erDiagram`
+    pub fn to_mermaid(&self) -> String {
+        let mut doc = String::from("erDiagram\n");
+        for table in &self.tables {
+            doc.push_str(&format!("    {} {{\n", entity_id(table)));
+            for column in &table.columns {
+                doc.push_str(&format!(
+                    "        {} {}",
+                    sanitize(&column.type_name),
+                    sanitize(&column.name)
+                ));
+                if column.is_pk {
+                    doc.push_str(" PK");
+                }
+                if let Some(comment) = column.comment.as_deref().filter(|c| !c.is_empty()) {
+                    doc.push_str(&format!(" \"{}\"", comment.replace(['"', '\n'], " ")));
+                }
+                doc.push('\n');
+            }
+            doc.push_str("    }\n");
+        }
+        for (table, fk, referenced) in self.relations() {
+            // 外键字段可空时被引用的一端为零或一
+            let parent = if is_optional(table, fk) { "o|" } else { "||" };
+            doc.push_str(&format!(
+                "    {} }}o--{parent} {} : \"{}\"\n",
+                entity_id(table),
+                entity_id(referenced),
+                fk.name.replace(['"', '\n'], " ")
+            ));
+        }
+        doc
+    }
+
+    /// 生成 Graphviz DOT，每张表是一个 HTML 表格节点
+    pub fn to_dot(&self) -> String {
+        let mut doc = String::from("digraph schema {\n    node [shape=plaintext];\n");
+        for table in &self.tables {
+            let mut label = format!(
+                "<table border=\"0\" cellborder=\"1\" cellspacing=\"0\"><tr><td bgcolor=\"lightgrey\"><b>{}</b></td></tr>",
+                html_escape(&table.table_name)
+            );
+            for column in &table.columns {
+                label.push_str(&format!(
+                    "<tr><td align=\"left\">{}</td></tr>",
+                    html_escape(&column_label(column))
+                ));
+            }
+            label.push_str("</table>");
+            doc.push_str(&format!(
+                "    \"{}\" [label=<{label}>];\n",
+                dot_id(table)
+            ));
+        }
+        for (table, fk, referenced) in self.relations() {
+            doc.push_str(&format!(
+                "    \"{}\" -> \"{}\" [label=\"{}\"];\n",
+                dot_id(table),
+                dot_id(referenced),
+                fk.name.replace('"', "\\\"")
+            ));
+        }
+        doc.push_str("}\n");
+        doc
+    }
+
+    /// 生成 PlantUML 实体图，主键字段在分隔线之上，非空字段以 `*` 标记。
+    /// 字段名按实体标识符处理，类型中的花括号、引号和换行会截断实体定义，替换为 `_`
+    pub fn to_plantuml(&self) -> String {
+        let mut doc = String::from("@startuml\n");
+        for table in &self.tables {
+            doc.push_str(&format!(
+                "entity \"{}\" as {} {{\n",
+                plantuml_text(&table.qualified_name().to_string()),
+                entity_id(table)
+            ));
+            let (pks, others): (Vec<&Column>, Vec<&Column>) =
+                table.columns.iter().partition(|c| c.is_pk);
+            for column in &pks {
+                doc.push_str(&format!(
+                    "  * {} : {} <<PK>>\n",
+                    sanitize(&column.name),
+                    plantuml_text(&column.type_name)
+                ));
+            }
+            if !pks.is_empty() {
+                doc.push_str("  --\n");
+            }
+            for column in &others {
+                let mandatory = if column.is_nullable { "" } else { "* " };
+                doc.push_str(&format!(
+                    "  {mandatory}{} : {}\n",
+                    sanitize(&column.name),
+                    plantuml_text(&column.type_name)
+                ));
+            }
+            doc.push_str("}\n");
+        }
+        for (table, fk, referenced) in self.relations() {
+            let parent = if is_optional(table, fk) { "o|" } else { "||" };
+            doc.push_str(&format!(
+                "{} }}o--{parent} {} : {}\n",
+                entity_id(table),
+                entity_id(referenced),
+                plantuml_text(&fk.name)
+            ));
+        }
+        doc.push_str("@enduml\n");
+        doc
+    }
+}
+
+impl Metadata {
+    /// 图中的关系：(引用表, 外键, 被引用表)，被引用表不在元数据中时跳过
+    fn relations(&self) -> Vec<(&TableInfo, &ForeignKey, &TableInfo)> {
+        self.tables
+            .iter()
+            .flat_map(|table| table.foreign_keys.iter().map(move |fk| (table, fk)))
+            .filter_map(|(table, fk)| {
+                let referenced = self.find_referenced(&fk.referenced_schema, &fk.referenced_table)?;
+                Some((table, fk, referenced))
+            })
+            .collect()
+    }
+}
+
+/// 外键字段中有可空的，即引用表的行可以不对应被引用表的行
+fn is_optional(table: &TableInfo, fk: &ForeignKey) -> bool {
+    fk.columns.iter().any(|name| {
+        table
+            .columns
+            .iter()
+            .any(|c| c.name == *name && c.is_nullable)
+    })
+}
+
+/// 图中实体的标识符，由 schema 与表名组成
+fn entity_id(table: &TableInfo) -> String {
+    if table.schema.is_empty() {
+        sanitize(&table.table_name)
+    } else {
+        sanitize(&format!("{}_{}", table.schema, table.table_name))
+    }
+}
+
+/// DOT 中节点的名称，即带引号的限定名
+fn dot_id(table: &TableInfo) -> String {
+    table.qualified_name().to_string().replace('"', "\\\"")
+}
+
+/// 替换标识符中不被支持的字符
+fn sanitize(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '_' || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// PlantUML 实体中的文字，去掉会截断实体定义的花括号、引号和换行
+fn plantuml_text(text: &str) -> String {
+    text.chars()
+        .map(|c| {
+            if matches!(c, '{' | '}' | '"') || c.is_control() {
+                '_'
+            } else {
+                c
+            }
+        })
+        .collect()
+}
+
+/// DOT 节点中字段的文字，如 `id : int4 (PK)`
+fn column_label(column: &Column) -> String {
+    let mut label = format!("{} : {}", column.name, column.type_name);
+    if column.is_pk {
+        label.push_str(" (PK)");
+    }
+    label
+}
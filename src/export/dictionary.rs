@@ -0,0 +1,195 @@
+use crate::modal::{Column, ForeignKey, IndexInfo, Metadata, TableInfo, ViewsInfo};
+
+/// 字段表的表头
+const COLUMN_HEADERS: [&str; 6] = ["字段", "类型", "可空", "默认值", "主键", "注释"];
+
+/// 索引表的表头
+const INDEX_HEADERS: [&str; 3] = ["索引", "字段", "唯一"];
+
+/// 外键表的表头
+const FOREIGN_KEY_HEADERS: [&str; 5] = ["外键", "字段", "引用", "更新时", "删除时"];
+
+impl Metadata {
+    /// 生成 Markdown 格式的数据字典，每张表/视图一节
+    pub fn to_markdown(&self) -> String {
+        let mut doc = String::from("# 数据字典\n");
+        for table in &self.tables {
+            doc.push_str(&table_markdown(table));
+        }
+        for view in &self.views {
+            doc.push_str(&view_markdown(view));
+        }
+        doc
+    }
+
+    /// 生成 HTML 格式的数据字典，每张表/视图一节
+    pub fn to_html(&self) -> String {
+        let mut doc = String::from(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>数据字典</title>\n</head>\n<body>\n<h1>数据字典</h1>\n",
+        );
+        for table in &self.tables {
+            doc.push_str(&table_html(table));
+        }
+        for view in &self.views {
+            doc.push_str(&view_html(view));
+        }
+        doc.push_str("</body>\n</html>\n");
+        doc
+    }
+}
+
+fn table_markdown(table: &TableInfo) -> String {
+    let mut doc = format!(
+        "\n## {}\n\n",
+        md_escape(&table.qualified_name().to_string())
+    );
+    if let Some(comment) = table.comment.as_deref().filter(|c| !c.is_empty()) {
+        doc.push_str(&format!("{}\n\n", md_escape(comment)));
+    }
+    doc.push_str(&md_table(&COLUMN_HEADERS, &column_rows(&table.columns)));
+    let indexes = index_rows(&table.index_columns);
+    if !indexes.is_empty() {
+        doc.push_str("\n### 索引\n\n");
+        doc.push_str(&md_table(&INDEX_HEADERS, &indexes));
+    }
+    if !table.foreign_keys.is_empty() {
+        doc.push_str("\n### 外键\n\n");
+        doc.push_str(&md_table(&FOREIGN_KEY_HEADERS, &foreign_key_rows(&table.foreign_keys)));
+    }
+    doc
+}
+
+fn view_markdown(view: &ViewsInfo) -> String {
+    let mut doc = format!(
+        "\n## {}（视图）\n\n",
+        md_escape(&view.qualified_name().to_string())
+    );
+    doc.push_str(&md_table(&COLUMN_HEADERS, &column_rows(&view.columns)));
+    doc
+}
+
+fn table_html(table: &TableInfo) -> String {
+    let mut doc = format!(
+        "<h2>{}</h2>\n",
+        html_escape(&table.qualified_name().to_string())
+    );
+    if let Some(comment) = table.comment.as_deref().filter(|c| !c.is_empty()) {
+        doc.push_str(&format!("<p>{}</p>\n", html_escape(comment)));
+    }
+    doc.push_str(&html_table(&COLUMN_HEADERS, &column_rows(&table.columns)));
+    let indexes = index_rows(&table.index_columns);
+    if !indexes.is_empty() {
+        doc.push_str("<h3>索引</h3>\n");
+        doc.push_str(&html_table(&INDEX_HEADERS, &indexes));
+    }
+    if !table.foreign_keys.is_empty() {
+        doc.push_str("<h3>外键</h3>\n");
+        doc.push_str(&html_table(&FOREIGN_KEY_HEADERS, &foreign_key_rows(&table.foreign_keys)));
+    }
+    doc
+}
+
+fn view_html(view: &ViewsInfo) -> String {
+    let mut doc = format!(
+        "<h2>{}（视图）</h2>\n",
+        html_escape(&view.qualified_name().to_string())
+    );
+    doc.push_str(&html_table(&COLUMN_HEADERS, &column_rows(&view.columns)));
+    doc
+}
+
+/// 字段表的各行
+fn column_rows(columns: &[Column]) -> Vec<Vec<String>> {
+    columns
+        .iter()
+        .map(|column| {
+            vec![
+                column.name.clone(),
+                column.display_type(),
+                yes_no(column.is_nullable),
+                column.column_def.clone().unwrap_or_default(),
+                yes_no(column.is_pk),
+                column.comment.clone().unwrap_or_default(),
+            ]
+        })
+        .collect()
+}
+
+/// 索引表的各行，同一索引的多个字段合并为一行
+fn index_rows(indexes: &[IndexInfo]) -> Vec<Vec<String>> {
+    let mut rows: Vec<Vec<String>> = Vec::new();
+    for index in indexes {
+        match rows.iter_mut().find(|row| row[0] == index.index_name) {
+            Some(row) => row[1] = format!("{}, {}", row[1], index.column_name),
+            None => rows.push(vec![
+                index.index_name.clone(),
+                index.column_name.clone(),
+                yes_no(index.is_unique),
+            ]),
+        }
+    }
+    rows
+}
+
+/// 外键表的各行，引用列形如 `public.users(id)`
+fn foreign_key_rows(foreign_keys: &[ForeignKey]) -> Vec<Vec<String>> {
+    foreign_keys
+        .iter()
+        .map(|fk| {
+            vec![
+                fk.name.clone(),
+                fk.columns.join(", "),
+                format!("{}({})", fk.referenced_name(), fk.referenced_columns.join(", ")),
+                fk.on_update.clone(),
+                fk.on_delete.clone(),
+            ]
+        })
+        .collect()
+}
+
+fn yes_no(value: bool) -> String {
+    if value { "是" } else { "否" }.to_string()
+}
+
+fn md_table(headers: &[&str], rows: &[Vec<String>]) -> String {
+    let mut table = format!("| {} |\n", headers.join(" | "));
+    table.push_str(&format!("|{}\n", " --- |".repeat(headers.len())));
+    for row in rows {
+        let cells: Vec<String> = row.iter().map(|cell| md_escape(cell)).collect();
+        table.push_str(&format!("| {} |\n", cells.join(" | ")));
+    }
+    table
+}
+
+fn html_table(headers: &[&str], rows: &[Vec<String>]) -> String {
+    let mut table = String::from("<table>\n<tr>");
+    for header in headers {
+        table.push_str(&format!("<th>{header}</th>"));
+    }
+    table.push_str("</tr>\n");
+    for row in rows {
+        table.push_str("<tr>");
+        for cell in row {
+            table.push_str(&format!("<td>{}</td>", html_escape(cell)));
+        }
+        table.push_str("</tr>\n");
+    }
+    table.push_str("</table>\n");
+    table
+}
+
+/// 转义 Markdown 表格中的特殊字符，换行替换为 `<br>`
+fn md_escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('|', "\\|")
+        .replace("\r\n", "<br>")
+        .replace('\n', "<br>")
+}
+
+pub(crate) fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
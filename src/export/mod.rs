@@ -0,0 +1,46 @@
+pub mod anonymize;
+pub mod avro;
+pub mod diagram;
+pub mod dictionary;
+pub mod openapi;
+#[cfg(feature = "arrow")]
+pub mod parquet;
+pub mod protobuf;
+pub mod query;
+
+use sha2::{Digest, Sha256};
+
+use crate::codegen::to_snake_case;
+
+/// SHA-256 摘要的十六进制表示
+pub(crate) fn sha256_hex(data: &[u8]) -> String {
+    Sha256::digest(data)
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+/// 转为 Avro/Protobuf 可用的字段名：snake_case，只含 ASCII 字母、数字和下划线，不以数字开头
+pub(crate) fn schema_field_name(name: &str) -> String {
+    let mut field: String = to_snake_case(name)
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    if field.is_empty() || field.starts_with(|c: char| c.is_ascii_digit()) {
+        field.insert(0, '_');
+    }
+    field
+}
+
+/// 转为 Avro record / Protobuf message 名：PascalCase，只含 ASCII 字母和数字
+pub(crate) fn schema_type_name(name: &str) -> String {
+    let type_name: String = crate::codegen::to_pascal_case(name)
+        .chars()
+        .filter(char::is_ascii_alphanumeric)
+        .collect();
+    if type_name.is_empty() || type_name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("T{type_name}")
+    } else {
+        type_name
+    }
+}
@@ -0,0 +1,127 @@
+use serde_json::{Map, Value, json};
+
+use super::schema_type_name;
+use crate::modal::{Column, FieldTypeEnum, Metadata};
+
+#[cfg(feature = "yaml")]
+use crate::error::MetaError;
+
+impl Metadata {
+    /// 生成 OpenAPI 3 的 `components.schemas`，每张表/视图一个 schema，注释作为 description
+    pub fn to_openapi(&self) -> Value {
+        let mut schemas = Map::new();
+        for table in &self.tables {
+            schemas.insert(
+                schema_type_name(&table.table_name),
+                object_schema(table.comment.as_deref(), &table.columns),
+            );
+        }
+        for view in &self.views {
+            schemas.insert(
+                schema_type_name(&view.view_name),
+                object_schema(None, &view.columns),
+            );
+        }
+        json!({ "components": { "schemas": schemas } })
+    }
+
+    /// 以 JSON 文本输出 OpenAPI 组件定义
+    pub fn to_openapi_json(&self) -> String {
+        // Value 的序列化不会失败
+        serde_json::to_string_pretty(&self.to_openapi()).unwrap_or_default()
+    }
+
+    /// 以 YAML 文本输出 OpenAPI 组件定义
+    #[cfg(feature = "yaml")]
+    pub fn to_openapi_yaml(&self) -> Result<String, MetaError> {
+        serde_yaml::to_string(&self.to_openapi())
+            .map_err(|e| MetaError::BadRequest(format!("YAML序列化失败: {e}")))
+    }
+}
+
+/// 表或视图对应的 object schema，非空字段列入 required
+fn object_schema(comment: Option<&str>, columns: &[Column]) -> Value {
+    let mut schema = Map::new();
+    schema.insert("type".into(), "object".into());
+    if let Some(description) = comment.filter(|c| !c.is_empty()) {
+        schema.insert("description".into(), description.into());
+    }
+    let required: Vec<&str> = columns
+        .iter()
+        .filter(|c| !c.is_nullable)
+        .map(|c| c.name.as_str())
+        .collect();
+    if !required.is_empty() {
+        schema.insert("required".into(), json!(required));
+    }
+    let properties: Map<String, Value> = columns
+        .iter()
+        .map(|c| (c.name.clone(), property_schema(c)))
+        .collect();
+    schema.insert("properties".into(), properties.into());
+    Value::Object(schema)
+}
+
+/// 字段对应的属性 schema
+fn property_schema(column: &Column) -> Value {
+    let mut property = match field_openapi_type(&column.column_type, &column.type_name) {
+        Value::Object(property) => property,
+        _ => Map::new(),
+    };
+    if matches!(
+        column.column_type,
+        FieldTypeEnum::String | FieldTypeEnum::Character
+    ) && column.length > 0
+    {
+        property.insert("maxLength".into(), column.length.into());
+    }
+    if column.is_nullable {
+        property.insert("nullable".into(), true.into());
+    }
+    if let Some(description) = column.comment.as_deref().filter(|c| !c.is_empty()) {
+        property.insert("description".into(), description.into());
+    }
+    Value::Object(property)
+}
+
+/// 字段类型对应的 OpenAPI 类型与格式
+fn field_openapi_type(column_type: &FieldTypeEnum, type_name: &str) -> Value {
+    let typed = |t: &str, format: &str| json!({"type": t, "format": format});
+    match column_type {
+        FieldTypeEnum::Integer => typed("integer", "int32"),
+        FieldTypeEnum::Long | FieldTypeEnum::BigInt => typed("integer", "int64"),
+        FieldTypeEnum::Float => typed("number", "float"),
+        FieldTypeEnum::Double => typed("number", "double"),
+        FieldTypeEnum::Boolean => json!({"type": "boolean"}),
+        FieldTypeEnum::ByteArray | FieldTypeEnum::Blob => typed("string", "byte"),
+        // PostgreSQL 的日期时间类型都映射为 Date，需要按类型名细分
+        FieldTypeEnum::Date => {
+            let type_name = type_name.to_lowercase();
+            if type_name.starts_with("timestamp") || type_name == "datetime" {
+                typed("string", "date-time")
+            } else if type_name.starts_with("time") {
+                typed("string", "time")
+            } else {
+                typed("string", "date")
+            }
+        }
+        FieldTypeEnum::LocalDate => typed("string", "date"),
+        FieldTypeEnum::Time | FieldTypeEnum::LocalTime => typed("string", "time"),
+        FieldTypeEnum::LocalDateTime | FieldTypeEnum::Timestamp => typed("string", "date-time"),
+        // 定点数以字符串传输，避免精度丢失
+        FieldTypeEnum::BigDec | FieldTypeEnum::Decimal { .. } => typed("string", "decimal"),
+        FieldTypeEnum::Uuid => typed("string", "uuid"),
+        // JSON 可以是任意值，不限定类型
+        FieldTypeEnum::Json => json!({}),
+        FieldTypeEnum::Array(element) => json!({
+            "type": "array",
+            "items": field_openapi_type(element, type_name.trim_start_matches('_')),
+        }),
+        FieldTypeEnum::String
+        | FieldTypeEnum::Character
+        | FieldTypeEnum::Object
+        | FieldTypeEnum::Clob
+        | FieldTypeEnum::Interval
+        | FieldTypeEnum::Custom(_) => json!({"type": "string"}),
+    }
+}
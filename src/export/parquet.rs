@@ -0,0 +1,235 @@
+use std::fs::File;
+use std::path::Path;
+use std::sync::Arc;
+
+use ::parquet::arrow::ArrowWriter;
+use ::parquet::basic::{Compression, ZstdLevel};
+use ::parquet::file::properties::WriterProperties;
+use arrow::array::{ArrayRef, BinaryBuilder, RecordBatch, StringArray};
+use arrow::compute::{CastOptions, cast_with_options};
+use arrow::datatypes::{DataType, Field, Schema, TimeUnit};
+use derive_builder::Builder;
+use futures::StreamExt;
+
+use crate::error::MetaError;
+use crate::ident::{qualified_name, quote_ident};
+use crate::meta::MetadataService;
+use crate::modal::{Column, FieldTypeEnum, TableInfo};
+use crate::sample::Value;
+
+/// Parquet 的压缩算法
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ParquetCompression {
+    Uncompressed,
+    #[default]
+    Snappy,
+    Zstd,
+}
+
+impl From<ParquetCompression> for Compression {
+    fn from(compression: ParquetCompression) -> Self {
+        match compression {
+            ParquetCompression::Uncompressed => Compression::UNCOMPRESSED,
+            ParquetCompression::Snappy => Compression::SNAPPY,
+            ParquetCompression::Zstd => Compression::ZSTD(ZstdLevel::default()),
+        }
+    }
+}
+
+/// Parquet 导出选项
+#[derive(Debug, Clone, Builder)]
+#[builder(default)]
+pub struct ParquetOptions {
+    // 每批从数据库读取的行数，每批写为一个 record batch
+    pub batch_size: usize,
+    // 压缩算法
+    pub compression: ParquetCompression,
+}
+
+impl Default for ParquetOptions {
+    fn default() -> Self {
+        Self {
+            batch_size: 8192,
+            compression: ParquetCompression::default(),
+        }
+    }
+}
+
+impl MetadataService {
+    /// 将已采集到的表的数据导出为 Parquet 文件，返回导出的行数。
+    /// 字段按 [`FieldTypeEnum`] 映射为 Arrow 类型，JSON、数组、区间等没有对应类型的字段写为字符串；
+    /// 数据分批读取、分批写入，不会一次加载到内存
+    pub async fn export_table_to_parquet(
+        &self,
+        table: &TableInfo,
+        path: impl AsRef<Path>,
+        options: &ParquetOptions,
+    ) -> Result<u64, MetaError> {
+        if table.columns.is_empty() {
+            return Err(MetaError::InvalidArgument(format!(
+                "表没有字段: {}",
+                table.qualified_name()
+            )));
+        }
+        let dialect = self.dialect();
+        let columns: Vec<_> = table
+            .columns
+            .iter()
+            .map(|c| quote_ident(&c.name, dialect))
+            .collect();
+        let sql = format!(
+            "SELECT {} FROM {}",
+            columns.join(", "),
+            qualified_name(&table.schema, &table.table_name, dialect)
+        );
+        let fields: Vec<_> = table
+            .columns
+            .iter()
+            .map(|c| Field::new(&c.name, arrow_type(c), c.is_nullable))
+            .collect();
+        let schema = Arc::new(Schema::new(fields));
+
+        let export = async {
+            let handler = self.create_metadata_handler().await?;
+            let properties = WriterProperties::builder()
+                .set_compression(options.compression.into())
+                .build();
+            let file = File::create(path)?;
+            let mut writer = ArrowWriter::try_new(file, schema.clone(), Some(properties))?;
+            let mut batches = handler.query_stream(&sql, options.batch_size.max(1));
+            let mut count = 0;
+            while let Some(batch) = batches.next().await {
+                let batch = batch?;
+                let arrays = schema
+                    .fields()
+                    .iter()
+                    .enumerate()
+                    .map(|(index, field)| column_array(&batch.rows, index, field.data_type()))
+                    .collect::<Result<_, MetaError>>()?;
+                writer.write(&RecordBatch::try_new(schema.clone(), arrays)?)?;
+                count += batch.rows.len() as u64;
+            }
+            writer.close()?;
+            Ok(count)
+        };
+        self.guard(export, None).await
+    }
+}
+
+/// 字段对应的 Arrow 类型
+fn arrow_type(column: &Column) -> DataType {
+    match &column.column_type {
+        FieldTypeEnum::Integer => DataType::Int32,
+        FieldTypeEnum::Long | FieldTypeEnum::BigInt => DataType::Int64,
+        FieldTypeEnum::Float => DataType::Float32,
+        FieldTypeEnum::Double => DataType::Float64,
+        FieldTypeEnum::Boolean => DataType::Boolean,
+        FieldTypeEnum::ByteArray | FieldTypeEnum::Blob => DataType::Binary,
+        FieldTypeEnum::Decimal {
+            precision: Some(precision),
+            scale,
+        } if (1..=38).contains(precision) => {
+            DataType::Decimal128(*precision as u8, scale.unwrap_or(0) as i8)
+        }
+        // PostgreSQL 的日期时间类型都映射为 Date，需要按类型名细分
+        FieldTypeEnum::Date => {
+            let type_name = column.type_name.to_lowercase();
+            if type_name.starts_with("timestamptz") || type_name.contains("with time zone") {
+                DataType::Timestamp(TimeUnit::Microsecond, Some("+00:00".into()))
+            } else if type_name.starts_with("timestamp") || type_name == "datetime" {
+                DataType::Timestamp(TimeUnit::Microsecond, None)
+            } else if type_name.starts_with("time") {
+                DataType::Time64(TimeUnit::Microsecond)
+            } else {
+                DataType::Date32
+            }
+        }
+        FieldTypeEnum::LocalDate => DataType::Date32,
+        FieldTypeEnum::Time | FieldTypeEnum::LocalTime => DataType::Time64(TimeUnit::Microsecond),
+        FieldTypeEnum::LocalDateTime => DataType::Timestamp(TimeUnit::Microsecond, None),
+        FieldTypeEnum::Timestamp => {
+            DataType::Timestamp(TimeUnit::Microsecond, Some("+00:00".into()))
+        }
+        _ => DataType::Utf8,
+    }
+}
+
+/// 一批行中的一列，先按文本收集再转换为目标类型，无法转换的值报错而不是写为空值
+fn column_array(
+    rows: &[Vec<Value>],
+    index: usize,
+    data_type: &DataType,
+) -> Result<ArrayRef, MetaError> {
+    if *data_type == DataType::Binary {
+        let mut builder = BinaryBuilder::new();
+        for value in rows.iter().map(|row| row.get(index)) {
+            match value {
+                Some(Value::Bytes(bytes)) => builder.append_value(bytes),
+                // PostgreSQL 文本协议下 bytea 为 `\x` 开头的十六进制
+                Some(Value::Text(text)) => match decode_hex(text) {
+                    Some(bytes) => builder.append_value(bytes),
+                    None => builder.append_value(text),
+                },
+                _ => builder.append_null(),
+            }
+        }
+        return Ok(Arc::new(builder.finish()));
+    }
+
+    let with_offset = matches!(data_type, DataType::Timestamp(_, Some(_)));
+    let text: StringArray = rows
+        .iter()
+        .map(|row| {
+            let text = text_value(row.get(index)?)?;
+            Some(if with_offset {
+                complete_offset(text)
+            } else {
+                text
+            })
+        })
+        .collect();
+    if *data_type == DataType::Utf8 {
+        return Ok(Arc::new(text));
+    }
+    let options = CastOptions {
+        safe: false,
+        ..Default::default()
+    };
+    Ok(cast_with_options(&text, data_type, &options)?)
+}
+
+/// 值的文本形式，空值为 None
+fn text_value(value: &Value) -> Option<String> {
+    match value {
+        Value::Null => None,
+        Value::Bool(b) => Some(b.to_string()),
+        Value::Int(i) => Some(i.to_string()),
+        Value::Float(f) => Some(f.to_string()),
+        Value::Text(text) => Some(text.clone()),
+        Value::Bytes(bytes) => Some(String::from_utf8_lossy(bytes).into_owned()),
+    }
+}
+
+/// PostgreSQL 输出的时区偏移只有小时（`+08`），补全为 `+08:00`
+fn complete_offset(mut text: String) -> String {
+    let bytes = text.as_bytes();
+    if bytes.len() > 3
+        && matches!(bytes[bytes.len() - 3], b'+' | b'-')
+        && bytes[bytes.len() - 2..].iter().all(u8::is_ascii_digit)
+    {
+        text.push_str(":00");
+    }
+    text
+}
+
+/// 解析 `\x` 开头的十六进制文本
+fn decode_hex(text: &str) -> Option<Vec<u8>> {
+    let digits = text.strip_prefix("\\x")?;
+    if digits.len() % 2 != 0 {
+        return None;
+    }
+    (0..digits.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(digits.get(i..i + 2)?, 16).ok())
+        .collect()
+}
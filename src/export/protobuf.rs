@@ -0,0 +1,163 @@
+use std::collections::HashMap;
+
+use derive_builder::Builder;
+
+use super::{schema_field_name, schema_type_name};
+use crate::modal::{Column, FieldTypeEnum, Metadata, TableInfo, ViewsInfo};
+
+/// 时间戳使用的 Protobuf 内置类型
+const TIMESTAMP_TYPE: &str = "google.protobuf.Timestamp";
+
+/// Protobuf 定义生成选项
+#[derive(Debug, Clone, Default, Builder)]
+#[builder(default)]
+pub struct ProtoOptions {
+    // proto 包名，为空时不输出 package 语句
+    pub package: String,
+    // 是否为视图生成 message
+    pub include_views: bool,
+    // 类型覆盖，键为 `表名.列名` 或数据库类型名（小写），值为 Protobuf 类型，以 `repeated ` 开头表示重复字段
+    pub type_overrides: HashMap<String, String>,
+}
+
+impl ProtoOptions {
+    /// 字段的 Protobuf 类型及是否为 repeated，优先使用类型覆盖
+    fn proto_type(&self, table: &str, column: &Column) -> (String, bool) {
+        let overridden = self
+            .type_overrides
+            .get(&format!("{table}.{}", column.name))
+            .or_else(|| self.type_overrides.get(&column.type_name.to_lowercase()));
+        match overridden {
+            Some(t) => match t.strip_prefix("repeated ") {
+                Some(element) => (element.trim().to_string(), true),
+                None => (t.clone(), false),
+            },
+            None => field_proto_type(&column.column_type, &column.type_name),
+        }
+    }
+}
+
+impl Metadata {
+    /// 生成包含所有表（及视图）message 的 proto3 文件
+    pub fn to_proto(&self, options: &ProtoOptions) -> String {
+        let mut messages = String::new();
+        for table in &self.tables {
+            messages.push('\n');
+            messages.push_str(&table.to_proto_message(options));
+        }
+        for view in self.views.iter().filter(|_| options.include_views) {
+            messages.push('\n');
+            messages.push_str(&view.to_proto_message(options));
+        }
+
+        let mut proto = String::from("syntax = \"proto3\";\n");
+        if !options.package.is_empty() {
+            proto.push_str(&format!("\npackage {};\n", options.package));
+        }
+        if messages.contains(TIMESTAMP_TYPE) {
+            proto.push_str("\nimport \"google/protobuf/timestamp.proto\";\n");
+        }
+        proto.push_str(&messages);
+        proto
+    }
+}
+
+impl TableInfo {
+    /// 生成表对应的 message 定义
+    pub fn to_proto_message(&self, options: &ProtoOptions) -> String {
+        render_message(
+            &self.table_name,
+            self.comment.as_deref(),
+            &self.columns,
+            options,
+        )
+    }
+}
+
+impl ViewsInfo {
+    /// 生成视图对应的 message 定义
+    pub fn to_proto_message(&self, options: &ProtoOptions) -> String {
+        render_message(&self.view_name, None, &self.columns, options)
+    }
+}
+
+fn render_message(
+    name: &str,
+    comment: Option<&str>,
+    columns: &[Column],
+    options: &ProtoOptions,
+) -> String {
+    let mut message = String::new();
+    push_comment(&mut message, comment, "");
+    message.push_str(&format!("message {} {{\n", schema_type_name(name)));
+    for (number, column) in columns.iter().enumerate() {
+        push_comment(&mut message, column.comment.as_deref(), "  ");
+        let (proto_type, repeated) = options.proto_type(name, column);
+        // proto3 中可空字段用 optional 区分未赋值与零值，repeated 字段本身可为空
+        let label = if repeated {
+            "repeated "
+        } else if column.is_nullable {
+            "optional "
+        } else {
+            ""
+        };
+        message.push_str(&format!(
+            "  {label}{proto_type} {} = {};\n",
+            schema_field_name(&column.name),
+            number + 1
+        ));
+    }
+    message.push_str("}\n");
+    message
+}
+
+/// 写入注释，多行注释逐行加 `//`
+fn push_comment(message: &mut String, comment: Option<&str>, indent: &str) {
+    if let Some(comment) = comment.filter(|c| !c.is_empty()) {
+        for line in comment.lines() {
+            message.push_str(&format!("{indent}// {}\n", line.trim_end()));
+        }
+    }
+}
+
+/// 字段类型对应的 Protobuf 类型及是否为 repeated
+fn field_proto_type(column_type: &FieldTypeEnum, type_name: &str) -> (String, bool) {
+    let proto_type = match column_type {
+        FieldTypeEnum::Integer => "int32",
+        FieldTypeEnum::Long => "int64",
+        FieldTypeEnum::BigInt => "uint64",
+        FieldTypeEnum::Float => "float",
+        FieldTypeEnum::Double => "double",
+        FieldTypeEnum::Boolean => "bool",
+        FieldTypeEnum::ByteArray | FieldTypeEnum::Blob => "bytes",
+        FieldTypeEnum::Timestamp | FieldTypeEnum::LocalDateTime => TIMESTAMP_TYPE,
+        // PostgreSQL 的日期时间类型都映射为 Date，只有时间戳使用 Timestamp
+        FieldTypeEnum::Date
+            if type_name.to_lowercase().starts_with("timestamp")
+                || type_name.eq_ignore_ascii_case("datetime") =>
+        {
+            TIMESTAMP_TYPE
+        }
+        // Protobuf 不支持嵌套的 repeated，多维数组按元素类型展开
+        FieldTypeEnum::Array(element) => {
+            let (element, _) = field_proto_type(element, type_name.trim_start_matches('_'));
+            return (element, true);
+        }
+        // 日期、时间、定点数等没有对应的标量类型，以字符串保留原值
+        FieldTypeEnum::Date
+        | FieldTypeEnum::LocalDate
+        | FieldTypeEnum::Time
+        | FieldTypeEnum::LocalTime
+        | FieldTypeEnum::BigDec
+        | FieldTypeEnum::Decimal { .. }
+        | FieldTypeEnum::Uuid
+        | FieldTypeEnum::Json
+        | FieldTypeEnum::Interval
+        | FieldTypeEnum::String
+        | FieldTypeEnum::Character
+        | FieldTypeEnum::Object
+        | FieldTypeEnum::Clob
+        | FieldTypeEnum::Custom(_) => "string",
+    };
+    (proto_type.to_string(), false)
+}
@@ -0,0 +1,119 @@
+use std::borrow::Cow;
+use std::io::Write;
+
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+
+use crate::error::MetaError;
+use crate::meta::MetadataService;
+use crate::sample::Value;
+
+/// 每批从数据库读取的行数
+const BATCH_SIZE: usize = 1000;
+
+/// 查询结果的导出格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RowFormat {
+    /// RFC 4180 CSV，首行为字段名；空值为空字段，空串为 `""`
+    Csv,
+    /// 每行一个 JSON 对象，空值为 null
+    NdJson,
+}
+
+impl MetadataService {
+    /// 流式执行查询并按 `format` 写入 `writer`，返回写出的行数。
+    /// 结果按批读取，不会一次加载到内存；只读模式下会先校验语句
+    pub async fn export_query<W: Write>(
+        &self,
+        sql: &str,
+        format: RowFormat,
+        mut writer: W,
+    ) -> Result<u64, MetaError> {
+        self.check_read_only(sql)?;
+        let export = async {
+            let handler = self.create_metadata_handler().await?;
+            let mut batches = handler.query_stream(sql, BATCH_SIZE);
+            let mut header = format == RowFormat::Csv;
+            let mut count = 0;
+            while let Some(batch) = batches.next().await {
+                let batch = batch?;
+                if header && !batch.columns.is_empty() {
+                    let names: Vec<_> = batch.columns.iter().map(|c| csv_text(c)).collect();
+                    writeln!(writer, "{}", names.join(","))?;
+                }
+                header = false;
+                for row in &batch.rows {
+                    match format {
+                        RowFormat::Csv => write_csv_row(&mut writer, row)?,
+                        RowFormat::NdJson => write_json_row(&mut writer, &batch.columns, row)?,
+                    }
+                    count += 1;
+                }
+            }
+            writer.flush()?;
+            Ok(count)
+        };
+        self.guard(export, None).await
+    }
+}
+
+/// CSV 的一行，以 `\n` 结尾
+fn write_csv_row(writer: &mut impl Write, row: &[Value]) -> Result<(), MetaError> {
+    let fields: Vec<Cow<str>> = row
+        .iter()
+        .map(|value| match value {
+            Value::Null => Cow::Borrowed(""),
+            Value::Bool(b) => Cow::Owned(b.to_string()),
+            Value::Int(i) => Cow::Owned(i.to_string()),
+            Value::Float(f) => Cow::Owned(f.to_string()),
+            Value::Text(text) if text.is_empty() => Cow::Borrowed("\"\""),
+            Value::Text(text) => csv_text(text),
+            Value::Bytes(bytes) => Cow::Owned(hex(bytes)),
+        })
+        .collect();
+    writeln!(writer, "{}", fields.join(","))?;
+    Ok(())
+}
+
+/// 含分隔符、引号或换行的文本加引号，内部引号写两次
+fn csv_text(text: &str) -> Cow<'_, str> {
+    if text.contains([',', '"', '\r', '\n']) {
+        Cow::Owned(format!("\"{}\"", text.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(text)
+    }
+}
+
+/// NDJSON 的一行；没有字段名时（后端不提供）写为数组
+fn write_json_row(
+    writer: &mut impl Write,
+    columns: &[String],
+    row: &[Value],
+) -> Result<(), MetaError> {
+    let values = row
+        .iter()
+        .map(json_value)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| MetaError::BadRequest(format!("JSON 序列化失败: {e}")))?;
+    let json = if columns.is_empty() {
+        serde_json::Value::Array(values)
+    } else {
+        serde_json::Value::Object(columns.iter().cloned().zip(values).collect())
+    };
+    writeln!(writer, "{json}")?;
+    Ok(())
+}
+
+/// 二进制值写为 `\x` 开头的十六进制文本，与 PostgreSQL 的 bytea 输出一致
+fn json_value(value: &Value) -> Result<serde_json::Value, serde_json::Error> {
+    match value {
+        Value::Bytes(bytes) => Ok(serde_json::Value::String(hex(bytes))),
+        value => serde_json::to_value(value),
+    }
+}
+
+/// `\x` 开头的十六进制文本
+fn hex(bytes: &[u8]) -> String {
+    let digits: String = bytes.iter().map(|b| format!("{b:02x}")).collect();
+    format!("\\x{digits}")
+}
@@ -0,0 +1,120 @@
+use std::collections::BTreeMap;
+
+use crate::export::sha256_hex;
+use crate::modal::{Column, ForeignKey, IndexInfo, Metadata, TableInfo, ViewsInfo};
+
+/// 规范化文本中的字段分隔符，不会出现在标识符中
+const SEP: char = '\u{1f}';
+
+impl Metadata {
+    /// 整个库结构的指纹：与表、字段、索引、外键的顺序无关，注释不参与计算。
+    /// 两个环境指纹相同即可认为结构一致，不同时再用 [`Metadata::table_fingerprints`] 定位
+    pub fn fingerprint(&self) -> String {
+        let lines: Vec<String> = self
+            .table_fingerprints()
+            .into_iter()
+            .map(|(name, hash)| format!("{name}{SEP}{hash}"))
+            .collect();
+        sha256_hex(lines.join("\n").as_bytes())
+    }
+
+    /// 每张表/视图的指纹，键为 `schema.name`（不区分大小写时为小写）
+    pub fn table_fingerprints(&self) -> BTreeMap<String, String> {
+        let tables = self.tables.iter().map(|t| {
+            let name = self.normalize(&t.qualified_name().to_string());
+            (name, self.table_fingerprint(t))
+        });
+        let views = self.views.iter().map(|v| {
+            let name = self.normalize(&v.qualified_name().to_string());
+            (name, self.view_fingerprint(v))
+        });
+        tables.chain(views).collect()
+    }
+
+    /// 单张表的指纹
+    pub fn table_fingerprint(&self, table: &TableInfo) -> String {
+        let mut lines = vec![format!("table{SEP}{}", self.normalize(&table.pk_column))];
+        lines.extend(self.column_lines(&table.columns));
+        lines.extend(self.index_lines(&table.index_columns));
+        lines.extend(self.foreign_key_lines(&table.foreign_keys));
+        sha256_hex(lines.join("\n").as_bytes())
+    }
+
+    /// 单个视图的指纹
+    pub fn view_fingerprint(&self, view: &ViewsInfo) -> String {
+        let mut lines = vec!["view".to_string()];
+        lines.extend(self.column_lines(&view.columns));
+        sha256_hex(lines.join("\n").as_bytes())
+    }
+
+    /// 按字段名排序后的字段规范化文本，默认值按分类比较（`now()` 与 `CURRENT_TIMESTAMP` 视为相同）
+    fn column_lines(&self, columns: &[Column]) -> Vec<String> {
+        let mut lines: Vec<String> = columns
+            .iter()
+            .map(|c| {
+                [
+                    self.normalize(&c.name),
+                    c.type_name.to_lowercase(),
+                    c.length.to_string(),
+                    format!("{:?}", c.digit),
+                    c.is_nullable.to_string(),
+                    c.auto_increment.unwrap_or_default().to_string(),
+                    c.is_pk.to_string(),
+                    format!("{:?}", c.default_value()),
+                ]
+                .join(&SEP.to_string())
+            })
+            .collect();
+        lines.sort();
+        lines
+    }
+
+    /// 按索引名排序后的索引规范化文本，复合索引内的字段顺序保留
+    fn index_lines(&self, indexes: &[IndexInfo]) -> Vec<String> {
+        let mut grouped: BTreeMap<String, (Vec<String>, bool)> = BTreeMap::new();
+        for index in indexes {
+            let entry = grouped
+                .entry(self.normalize(&index.index_name))
+                .or_default();
+            entry.0.push(self.normalize(&index.column_name));
+            entry.1 = index.is_unique;
+        }
+        grouped
+            .into_iter()
+            .map(|(name, (columns, is_unique))| {
+                format!("{name}{SEP}{}{SEP}{is_unique}", columns.join(","))
+            })
+            .collect()
+    }
+
+    /// 排序后的外键规范化文本，约束名不参与计算（MySQL 等会自动生成约束名）
+    fn foreign_key_lines(&self, foreign_keys: &[ForeignKey]) -> Vec<String> {
+        let mut lines: Vec<String> = foreign_keys
+            .iter()
+            .map(|fk| {
+                let columns: Vec<_> = fk.columns.iter().map(|c| self.normalize(c)).collect();
+                let referenced: Vec<_> = fk.referenced_columns.iter().map(|c| self.normalize(c)).collect();
+                [
+                    "fk".to_string(),
+                    columns.join(","),
+                    self.normalize(&fk.referenced_name().to_string()),
+                    referenced.join(","),
+                    fk.on_update.clone(),
+                    fk.on_delete.clone(),
+                ]
+                .join(&SEP.to_string())
+            })
+            .collect();
+        lines.sort();
+        lines
+    }
+
+    /// 不区分大小写时标识符统一转为小写
+    pub(crate) fn normalize(&self, name: &str) -> String {
+        if self.case_insensitive {
+            name.to_lowercase()
+        } else {
+            name.to_string()
+        }
+    }
+}
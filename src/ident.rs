@@ -0,0 +1,122 @@
+use std::fmt::{self, Display};
+
+use serde::{Deserialize, Serialize};
+
+use crate::modal::DbType;
+
+/// SQL 方言，决定标识符的引用方式和默认 schema
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dialect {
+    Postgresql,
+    MySql,
+    Sqlite,
+}
+
+impl Dialect {
+    /// 方言的默认 schema，渲染时省略；MySQL 的 schema 即数据库，没有固定默认值
+    pub fn default_schema(&self) -> Option<&'static str> {
+        match self {
+            Dialect::Postgresql => Some("public"),
+            Dialect::MySql => None,
+            Dialect::Sqlite => Some("main"),
+        }
+    }
+
+    /// 标识符引用符
+    fn quote_char(&self) -> char {
+        match self {
+            Dialect::MySql => '`',
+            Dialect::Postgresql | Dialect::Sqlite => '"',
+        }
+    }
+}
+
+impl From<&DbType> for Dialect {
+    fn from(db_type: &DbType) -> Self {
+        match db_type {
+            // 第三方后端按标准 SQL 的双引号引用处理
+            // DuckDB 的语法与 PostgreSQL 接近
+            DbType::Postgresql | DbType::DuckDb | DbType::Mock | DbType::Custom(_) => {
+                Dialect::Postgresql
+            }
+            // ClickHouse 同样以反引号引用标识符，库即 schema
+            DbType::MySql | DbType::MariaDb | DbType::ClickHouse => Dialect::MySql,
+            DbType::Sqlite => Dialect::Sqlite,
+        }
+    }
+}
+
+/// 带 schema 的对象名，如 `public.user`
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct QualifiedName {
+    // 所在 schema，为空表示未限定
+    pub schema: String,
+    // 对象名
+    pub name: String,
+}
+
+impl QualifiedName {
+    pub fn new(schema: impl Into<String>, name: impl Into<String>) -> Self {
+        Self {
+            schema: schema.into(),
+            name: name.into(),
+        }
+    }
+
+    /// 按方言渲染为 SQL 中可用的名称，标识符总是被引用，方言默认 schema 会被省略
+    pub fn render(&self, dialect: Dialect) -> String {
+        self.render_with_default(dialect, dialect.default_schema())
+    }
+
+    /// 按方言渲染，省略指定的默认 schema（如 MySQL 当前连接的数据库）
+    pub fn render_with_default(&self, dialect: Dialect, default_schema: Option<&str>) -> String {
+        let name = quote_ident(&self.name, dialect);
+        if self.schema.is_empty() || default_schema == Some(self.schema.as_str()) {
+            name
+        } else {
+            format!("{}.{}", quote_ident(&self.schema, dialect), name)
+        }
+    }
+}
+
+impl Display for QualifiedName {
+    /// 不带引用的展示形式，用于日志和提示信息
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.schema.is_empty() {
+            write!(f, "{}", self.name)
+        } else {
+            write!(f, "{}.{}", self.schema, self.name)
+        }
+    }
+}
+
+/// 引用单个标识符，内部的引用符会被转义，大小写混合的名称（`"Order"`）和保留字（`user`）都能原样使用
+pub fn quote_ident(ident: &str, dialect: Dialect) -> String {
+    let q = dialect.quote_char();
+    let escaped = ident.replace(q, &format!("{q}{q}"));
+    format!("{q}{escaped}{q}")
+}
+
+/// 引用带 schema 的表名，schema 为空时只引用表名，如 `"public"."Order"`
+pub fn qualified_name(schema: &str, table: &str, dialect: Dialect) -> String {
+    QualifiedName::new(schema, table).render_with_default(dialect, None)
+}
+
+/// 引用字符串字面量；MySQL 默认把反斜杠视为转义符，需一并转义
+pub(crate) fn quote_literal(value: &str, dialect: Dialect) -> String {
+    let escaped = value.replace('\'', "''");
+    match dialect {
+        Dialect::MySql => format!("'{}'", escaped.replace('\\', "\\\\")),
+        Dialect::Postgresql | Dialect::Sqlite => format!("'{escaped}'"),
+    }
+}
+
+/// 引用后以逗号分隔的字面量列表，用于 `IN (...)`
+#[cfg(any(feature = "mysql", feature = "postgres"))]
+pub(crate) fn quote_literal_list<S: AsRef<str>>(values: &[S], dialect: Dialect) -> String {
+    values
+        .iter()
+        .map(|value| quote_literal(value.as_ref(), dialect))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
@@ -0,0 +1,522 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::ops::ControlFlow;
+
+use serde::{Deserialize, Serialize};
+use sqlparser::ast::{
+    Expr, Ident, ObjectName, Query, Select, SelectItem, SetExpr, Statement, TableAlias,
+    TableFactor, Visit, Visitor,
+};
+
+use crate::error::MetaError;
+use crate::ident::{Dialect, QualifiedName};
+use crate::modal::Metadata;
+use crate::sql_guard::parse_sql;
+
+/// 被查询引用的字段
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct ColumnRef {
+    // 字段所在的表或视图
+    pub table: QualifiedName,
+    // 字段名
+    pub column: String,
+}
+
+/// 查询的血缘：读取了哪些表和字段
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct QueryLineage {
+    // 读取的表和视图
+    pub tables: Vec<QualifiedName>,
+    // 读取的字段，`*` 会展开为全部字段
+    pub columns: Vec<ColumnRef>,
+    // 无法在元数据中解析的表名或字段名
+    pub unresolved: Vec<String>,
+}
+
+impl Metadata {
+    /// 解析一条 SELECT，借助已采集的元数据得出其读取的表和字段。
+    /// 结果是尽力而为的：所有子查询共用一个作用域，无法唯一确定来源的字段记入 `unresolved`
+    pub fn query_lineage(&self, sql: &str, dialect: Dialect) -> Result<QueryLineage, MetaError> {
+        let statements = parse_sql(sql, dialect)?;
+        let query = match statements.as_slice() {
+            [Statement::Query(query)] => query,
+            _ => {
+                return Err(MetaError::InvalidArgument(
+                    "血缘分析只支持单条SELECT语句".into(),
+                ));
+            }
+        };
+
+        let mut collector = Collector::default();
+        let _ = query.visit(&mut collector);
+        Ok(self.resolve(collector))
+    }
+
+    /// 将收集到的表、字段引用与元数据对照
+    fn resolve(&self, collector: Collector) -> QueryLineage {
+        let objects: Vec<(QualifiedName, Vec<&str>)> = self
+            .tables
+            .iter()
+            .map(|t| {
+                let columns = t.columns.iter().map(|c| c.name.as_str()).collect();
+                (t.qualified_name(), columns)
+            })
+            .chain(self.views.iter().map(|v| {
+                let columns = v.columns.iter().map(|c| c.name.as_str()).collect();
+                (v.qualified_name(), columns)
+            }))
+            .collect();
+
+        let mut tables = BTreeSet::new();
+        let mut columns = BTreeSet::new();
+        let mut unresolved = BTreeSet::new();
+        // 别名（或表名）-> 元数据中的对象下标
+        let mut scope: HashMap<String, usize> = HashMap::new();
+
+        for (name, alias) in &collector.relations {
+            if name.0.len() == 1
+                && collector
+                    .virtual_names
+                    .contains(&name.0[0].value.to_lowercase())
+            {
+                continue;
+            }
+            match find_object(&objects, name) {
+                Some(index) => {
+                    tables.insert(objects[index].0.clone());
+                    let key = alias
+                        .as_ref()
+                        .or(name.0.last())
+                        .map(|i| i.value.to_lowercase());
+                    scope.extend(key.map(|k| (k, index)));
+                }
+                None => {
+                    unresolved.insert(name.to_string());
+                }
+            }
+        }
+        let in_scope: BTreeSet<usize> = scope.values().copied().collect();
+
+        for wildcard in &collector.wildcards {
+            let indexes: Vec<usize> = match wildcard {
+                Some(qualifier) => qualifier
+                    .0
+                    .last()
+                    .and_then(|q| scope.get(&q.value.to_lowercase()))
+                    .into_iter()
+                    .copied()
+                    .collect(),
+                None => in_scope.iter().copied().collect(),
+            };
+            for index in indexes {
+                let (table, table_columns) = &objects[index];
+                columns.extend(table_columns.iter().map(|c| ColumnRef {
+                    table: table.clone(),
+                    column: c.to_string(),
+                }));
+            }
+        }
+
+        for parts in &collector.identifiers {
+            let Some((column, qualifier)) = parts.split_last() else {
+                continue;
+            };
+            let candidates: Vec<usize> = match qualifier.last() {
+                Some(q) => {
+                    let q = q.value.to_lowercase();
+                    if collector.virtual_names.contains(&q) {
+                        continue;
+                    }
+                    scope.get(&q).copied().into_iter().collect()
+                }
+                None => in_scope.iter().copied().collect(),
+            };
+            let matched: Vec<(usize, &str)> = candidates
+                .into_iter()
+                .filter_map(|index| {
+                    objects[index]
+                        .1
+                        .iter()
+                        .find(|c| c.eq_ignore_ascii_case(&column.value))
+                        .map(|c| (index, *c))
+                })
+                .collect();
+            match matched.as_slice() {
+                [(index, name)] => {
+                    columns.insert(ColumnRef {
+                        table: objects[*index].0.clone(),
+                        column: name.to_string(),
+                    });
+                }
+                // 未加限定的 SELECT 别名（如 ORDER BY 中引用）不算未解析
+                [] if qualifier.is_empty()
+                    && collector
+                        .select_aliases
+                        .contains(&column.value.to_lowercase()) => {}
+                _ => {
+                    let name = parts.iter().map(|p| p.value.as_str()).collect::<Vec<_>>();
+                    unresolved.insert(name.join("."));
+                }
+            }
+        }
+
+        QueryLineage {
+            tables: tables.into_iter().collect(),
+            columns: columns.into_iter().collect(),
+            unresolved: unresolved.into_iter().collect(),
+        }
+    }
+}
+
+impl Metadata {
+    /// 解析所有带查询语句的视图，填充 `column_lineage`；无法解析的视图跳过
+    pub fn resolve_view_lineage(&mut self, dialect: Dialect) {
+        let lineage: Vec<_> = self
+            .views
+            .iter()
+            .map(|view| {
+                let definition = view.definition.as_deref()?;
+                match self.view_column_lineage(definition, dialect) {
+                    Ok(lineage) => Some(lineage),
+                    Err(e) => {
+                        log::debug!("视图 {} 的血缘解析失败: {e}", view.qualified_name());
+                        None
+                    }
+                }
+            })
+            .collect();
+        for (view, lineage) in self.views.iter_mut().zip(lineage) {
+            let Some(lineage) = lineage else {
+                continue;
+            };
+            // 输出字段与采集到的视图字段一一对应时，以视图字段名为准（视图可以重命名字段）
+            let names: Vec<String> = if lineage.len() == view.columns.len() {
+                view.columns.iter().map(|c| c.name.clone()).collect()
+            } else {
+                lineage.iter().map(|(name, _)| name.clone()).collect()
+            };
+            view.column_lineage = names
+                .into_iter()
+                .zip(lineage)
+                .map(|(name, (_, sources))| (name, sources.into_iter().collect()))
+                .collect();
+        }
+    }
+
+    /// 解析视图的查询语句，按输出顺序给出每个字段及其来源字段。
+    /// 结果是尽力而为的：来源追溯到 FROM 中的表或视图（不继续展开视图），CTE 和子查询会逐层展开；
+    /// 常量、`count(*)` 等没有来源字段，无法唯一确定来源的字段引用被忽略，表达式中的标量子查询不解析
+    pub fn view_column_lineage(
+        &self,
+        definition: &str,
+        dialect: Dialect,
+    ) -> Result<Vec<(String, BTreeSet<ColumnRef>)>, MetaError> {
+        let statements = parse_sql(definition, dialect)?;
+        let query = match statements.as_slice() {
+            [Statement::Query(query)] => query,
+            [Statement::CreateView { query, .. }] => query,
+            _ => {
+                return Err(MetaError::InvalidArgument(
+                    "视图定义不是单条SELECT语句".into(),
+                ));
+            }
+        };
+        let mut resolver = LineageResolver {
+            metadata: self,
+            ctes: HashMap::new(),
+        };
+        Ok(resolver.query(query))
+    }
+}
+
+/// 关系（表、视图、CTE、子查询）的输出字段及各自的来源字段，按输出顺序
+type Relation = Vec<(String, BTreeSet<ColumnRef>)>;
+
+/// 逐层解析查询，得出每个输出字段的来源
+struct LineageResolver<'a> {
+    metadata: &'a Metadata,
+    // 已解析的 CTE，键为小写名称
+    ctes: HashMap<String, Relation>,
+}
+
+impl LineageResolver<'_> {
+    fn query(&mut self, query: &Query) -> Relation {
+        if let Some(with) = &query.with {
+            for cte in &with.cte_tables {
+                let relation = aliased(self.query(&cte.query), Some(&cte.alias));
+                self.ctes
+                    .insert(cte.alias.name.value.to_lowercase(), relation);
+            }
+        }
+        self.set_expr(&query.body)
+    }
+
+    /// 集合运算按位置合并各分支的来源，字段名取第一个分支
+    fn set_expr(&mut self, body: &SetExpr) -> Relation {
+        match body {
+            SetExpr::Select(select) => self.select(select),
+            SetExpr::Query(query) => self.query(query),
+            SetExpr::SetOperation { left, right, .. } => {
+                let mut relation = self.set_expr(left);
+                let right = self.set_expr(right);
+                for ((_, sources), (_, other)) in relation.iter_mut().zip(right) {
+                    sources.extend(other);
+                }
+                relation
+            }
+            _ => vec![],
+        }
+    }
+
+    fn select(&mut self, select: &Select) -> Relation {
+        // FROM 中的关系，键为别名或表名（小写）
+        let mut scope: Vec<(String, Relation)> = Vec::new();
+        for table in &select.from {
+            self.table_factor(&table.relation, &mut scope);
+            for join in &table.joins {
+                self.table_factor(&join.relation, &mut scope);
+            }
+        }
+
+        let mut output = Relation::new();
+        for item in &select.projection {
+            match item {
+                SelectItem::UnnamedExpr(expr) => {
+                    output.push((output_name(expr), sources(expr, &scope)));
+                }
+                SelectItem::ExprWithAlias { expr, alias } => {
+                    output.push((alias.value.clone(), sources(expr, &scope)));
+                }
+                SelectItem::Wildcard(_) => {
+                    for (_, relation) in &scope {
+                        output.extend(relation.iter().cloned());
+                    }
+                }
+                SelectItem::QualifiedWildcard(name, _) => {
+                    let key = name.0.last().map(|q| q.value.to_lowercase());
+                    if let Some((_, relation)) = scope.iter().find(|(k, _)| Some(k) == key.as_ref())
+                    {
+                        output.extend(relation.iter().cloned());
+                    }
+                }
+            }
+        }
+        output
+    }
+
+    fn table_factor(&mut self, factor: &TableFactor, scope: &mut Vec<(String, Relation)>) {
+        match factor {
+            TableFactor::Table { name, alias, .. } => {
+                let Some(last) = name.0.last() else {
+                    return;
+                };
+                let cte = (name.0.len() == 1)
+                    .then(|| self.ctes.get(&last.value.to_lowercase()))
+                    .flatten();
+                let relation = match cte {
+                    Some(relation) => relation.clone(),
+                    None => self.object(name),
+                };
+                let key = alias.as_ref().map_or(last, |a| &a.name);
+                scope.push((key.value.to_lowercase(), aliased(relation, alias.as_ref())));
+            }
+            TableFactor::Derived {
+                subquery, alias, ..
+            } => {
+                let relation = aliased(self.query(subquery), alias.as_ref());
+                let key = alias.as_ref().map(|a| a.name.value.to_lowercase());
+                scope.push((key.unwrap_or_default(), relation));
+            }
+            TableFactor::NestedJoin {
+                table_with_joins, ..
+            } => {
+                self.table_factor(&table_with_joins.relation, scope);
+                for join in &table_with_joins.joins {
+                    self.table_factor(&join.relation, scope);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// 元数据中的表或视图，每个字段的来源即自身；找不到时为空
+    fn object(&self, name: &ObjectName) -> Relation {
+        let (table, schema) = match name.0.as_slice() {
+            [.., schema, table] => (table, Some(schema)),
+            [table] => (table, None),
+            [] => return vec![],
+        };
+        let matches = |qualified: &QualifiedName| {
+            qualified.name.eq_ignore_ascii_case(&table.value)
+                && schema.is_none_or(|s| qualified.schema.eq_ignore_ascii_case(&s.value))
+        };
+        let columns = self
+            .metadata
+            .tables
+            .iter()
+            .map(|t| (t.qualified_name(), &t.columns))
+            .chain(
+                self.metadata
+                    .views
+                    .iter()
+                    .map(|v| (v.qualified_name(), &v.columns)),
+            )
+            .find(|(qualified, _)| matches(qualified));
+        let Some((qualified, columns)) = columns else {
+            return vec![];
+        };
+        columns
+            .iter()
+            .map(|c| {
+                let source = ColumnRef {
+                    table: qualified.clone(),
+                    column: c.name.clone(),
+                };
+                (c.name.clone(), BTreeSet::from([source]))
+            })
+            .collect()
+    }
+}
+
+/// 按别名中的字段列表（如 `t(a, b)`）重命名关系的字段
+fn aliased(mut relation: Relation, alias: Option<&TableAlias>) -> Relation {
+    if let Some(alias) = alias {
+        for ((name, _), column) in relation.iter_mut().zip(&alias.columns) {
+            *name = column.name.value.clone();
+        }
+    }
+    relation
+}
+
+/// 未指定别名的输出字段名：字段引用取字段名，其它表达式取其文本
+fn output_name(expr: &Expr) -> String {
+    match expr {
+        Expr::Identifier(ident) => ident.value.clone(),
+        Expr::CompoundIdentifier(parts) => {
+            parts.last().map(|p| p.value.clone()).unwrap_or_default()
+        }
+        _ => expr.to_string(),
+    }
+}
+
+/// 表达式引用的字段在 FROM 中对应的来源
+fn sources(expr: &Expr, scope: &[(String, Relation)]) -> BTreeSet<ColumnRef> {
+    let mut collector = Collector::default();
+    let _ = expr.visit(&mut collector);
+    let mut sources = BTreeSet::new();
+    for parts in &collector.identifiers {
+        let Some((column, qualifier)) = parts.split_last() else {
+            continue;
+        };
+        let key = qualifier.last().map(|q| q.value.to_lowercase());
+        let matched: Vec<&BTreeSet<ColumnRef>> = scope
+            .iter()
+            .filter(|(k, _)| key.as_ref().is_none_or(|key| key == k))
+            .filter_map(|(_, relation)| {
+                relation
+                    .iter()
+                    .find(|(name, _)| name.eq_ignore_ascii_case(&column.value))
+                    .map(|(_, sources)| sources)
+            })
+            .collect();
+        if let [found] = matched.as_slice() {
+            sources.extend(found.iter().cloned());
+        }
+    }
+    sources
+}
+
+/// 按名称在元数据中查找对象，忽略大小写；未限定 schema 时取第一个同名对象
+fn find_object(objects: &[(QualifiedName, Vec<&str>)], name: &ObjectName) -> Option<usize> {
+    let (table, schema) = match name.0.as_slice() {
+        [.., schema, table] => (table, Some(schema)),
+        [table] => (table, None),
+        [] => return None,
+    };
+    objects.iter().position(|(qualified, _)| {
+        qualified.name.eq_ignore_ascii_case(&table.value)
+            && schema.is_none_or(|s| qualified.schema.eq_ignore_ascii_case(&s.value))
+    })
+}
+
+/// 遍历语法树，收集表引用、字段引用和通配符
+#[derive(Default)]
+struct Collector {
+    // 表引用及其别名
+    relations: Vec<(ObjectName, Option<Ident>)>,
+    // CTE 和派生表的名称，引用它们的不是真实的表
+    virtual_names: HashSet<String>,
+    // 字段引用，可能带限定
+    identifiers: Vec<Vec<Ident>>,
+    // `*`（None）与 `t.*`
+    wildcards: Vec<Option<ObjectName>>,
+    // SELECT 列表中的别名
+    select_aliases: HashSet<String>,
+}
+
+impl Visitor for Collector {
+    type Break = ();
+
+    fn pre_visit_query(&mut self, query: &Query) -> ControlFlow<()> {
+        if let Some(with) = &query.with {
+            self.virtual_names.extend(
+                with.cte_tables
+                    .iter()
+                    .map(|cte| cte.alias.name.value.to_lowercase()),
+            );
+        }
+        self.collect_projection(&query.body);
+        ControlFlow::Continue(())
+    }
+
+    fn pre_visit_table_factor(&mut self, table_factor: &TableFactor) -> ControlFlow<()> {
+        match table_factor {
+            TableFactor::Table { name, alias, .. } => {
+                self.relations
+                    .push((name.clone(), alias.as_ref().map(|a| a.name.clone())));
+            }
+            TableFactor::Derived {
+                alias: Some(alias), ..
+            } => {
+                self.virtual_names.insert(alias.name.value.to_lowercase());
+            }
+            _ => {}
+        }
+        ControlFlow::Continue(())
+    }
+
+    fn pre_visit_expr(&mut self, expr: &Expr) -> ControlFlow<()> {
+        match expr {
+            Expr::Identifier(ident) => self.identifiers.push(vec![ident.clone()]),
+            Expr::CompoundIdentifier(parts) => self.identifiers.push(parts.clone()),
+            _ => {}
+        }
+        ControlFlow::Continue(())
+    }
+}
+
+impl Collector {
+    /// 收集 SELECT 列表中的通配符和别名，集合运算的各分支都会处理
+    fn collect_projection(&mut self, body: &SetExpr) {
+        match body {
+            SetExpr::Select(select) => {
+                for item in &select.projection {
+                    match item {
+                        SelectItem::Wildcard(_) => self.wildcards.push(None),
+                        SelectItem::QualifiedWildcard(name, _) => {
+                            self.wildcards.push(Some(name.clone()))
+                        }
+                        SelectItem::ExprWithAlias { alias, .. } => {
+                            self.select_aliases.insert(alias.value.to_lowercase());
+                        }
+                        SelectItem::UnnamedExpr(_) => {}
+                    }
+                }
+            }
+            SetExpr::SetOperation { left, right, .. } => {
+                self.collect_projection(left);
+                self.collect_projection(right);
+            }
+            _ => {}
+        }
+    }
+}
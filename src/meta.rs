@@ -1,55 +1,950 @@
 #![allow(dead_code, unused_variables)]
+use std::collections::HashMap;
+use std::fmt::{self, Debug};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, OnceLock, RwLock};
+use std::time::{Duration, Instant};
+
 use async_trait::async_trait;
+use futures::{Stream, StreamExt, TryStreamExt, stream, stream::BoxStream};
+use tokio::sync::Mutex;
+use tokio_util::sync::CancellationToken;
 
+#[cfg(feature = "clickhouse")]
+use crate::clickhouse_meta::ClickHouseMeta;
+#[cfg(feature = "duckdb")]
+use crate::duckdb_meta::DuckDbMeta;
+#[cfg(feature = "mysql")]
+use crate::mysql_meta::MysqlMeta;
+#[cfg(feature = "postgres")]
+use crate::pg_meta::PgMeta;
 use crate::{
     error::MetaError,
+    ident::{Dialect, QualifiedName},
+    metrics::{Metrics, MetricsSink},
+    mock_meta::MockMeta,
     // 推测这里可能是拼写错误，应该是 `model` 而非 `modal`
-    modal::{ConnConfig, DbType, Metadata, TableInfo, ViewsInfo},
-    mysql_meta::MysqlMeta,
-    pg_meta::PgMeta,
+    modal::{
+        Column, ConnConfig, DbType, FieldTypeEnum, ForeignServer, ForeignTable, HarvestEvent,
+        HarvestSummary, Metadata, Privilege, Progress, TableInfo, ViewsInfo,
+    },
+    page::{self, Page},
+    sample::{SampleRows, SampleStrategy, Value},
+    sql_guard,
+    ssh_tunnel::{SshTunnel, SshTunnelConfig},
+    supervisor::{ServiceStatus, Supervisor, SupervisorOptions},
 };
 
 // 数据库元数据采集
 #[derive(Debug, Clone)]
 pub struct MetadataService {
     pub connection: ConnConfig,
+    // 自定义类型映射，优先于内置映射
+    type_mapper: Option<TypeMapper>,
+    // 只读模式，开启后 query/count 只允许执行单条 SELECT
+    read_only: bool,
+    // 没有采集到任何表和视图时是否视为错误，默认只记录警告
+    fail_on_empty: bool,
+    // 是否从表和字段注释中解析标签
+    comment_tags: bool,
+    // 是否采集外部服务器及其用户映射
+    foreign_servers: bool,
+    // 是否解析视图定义得出字段血缘
+    view_lineage: bool,
+    // 是否采集数据库给出的建表/建视图语句
+    create_sql: bool,
+    // 内存模拟后端，设置后不再连接数据库
+    mock: Option<MockMeta>,
+    // 单次调用（含建立连接）的超时时间
+    timeout: Option<Duration>,
+    // 指标接收器，记录后端调用耗时与错误
+    metrics: Option<Metrics>,
+    // 配置了 SSH 隧道时，首次连接建立的隧道，供后续调用复用
+    tunnel: Arc<Mutex<Option<SshTunnel>>>,
+    // 常驻模式，开启后在调用之间复用连接池，连接断开时自动重建
+    supervisor: Option<Supervisor>,
+}
+
+type TypeMapperFn = dyn Fn(&str) -> Option<FieldTypeEnum> + Send + Sync;
+
+/// 自定义类型映射器，入参为数据库类型名，返回 None 时使用内置映射
+#[derive(Clone)]
+pub struct TypeMapper(Arc<TypeMapperFn>);
+
+impl Debug for TypeMapper {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("TypeMapper")
+    }
+}
+
+impl TypeMapper {
+    /// 用自定义映射覆盖字段类型
+    pub fn apply(&self, columns: &mut [Column]) {
+        for column in columns {
+            if let Some(column_type) = (self.0)(&column.type_name) {
+                column.column_type = column_type;
+            }
+        }
+    }
+}
+
+/// 第三方后端工厂返回的 future
+pub type BackendFuture =
+    Pin<Box<dyn Future<Output = Result<Box<dyn MetaTrait>, MetaError>> + Send>>;
+
+type BackendFactory = dyn Fn(ConnConfig) -> BackendFuture + Send + Sync;
+
+/// 已注册的第三方后端，键为 `DbType::Custom` 中的名称
+fn backends() -> &'static RwLock<HashMap<String, Arc<BackendFactory>>> {
+    static BACKENDS: OnceLock<RwLock<HashMap<String, Arc<BackendFactory>>>> = OnceLock::new();
+    BACKENDS.get_or_init(Default::default)
 }
 
 impl MetadataService {
+    /// 注册第三方后端，`DbType::Custom(name)` 的连接将交由 `factory` 创建处理器。
+    /// 同名后端重复注册时以后注册的为准
+    pub fn register_backend<F, Fut>(name: impl Into<String>, factory: F)
+    where
+        F: Fn(ConnConfig) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<Box<dyn MetaTrait>, MetaError>> + Send + 'static,
+    {
+        let factory: Arc<BackendFactory> = Arc::new(move |config| Box::pin(factory(config)));
+        backends()
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(name.into(), factory);
+    }
+
     pub fn new(connection_config: ConnConfig) -> Result<Self, MetaError> {
         connection_config.validate()?;
-        Ok(Self { connection: connection_config })
+        Ok(Self {
+            read_only: connection_config.read_only,
+            connection: connection_config,
+            type_mapper: None,
+            fail_on_empty: false,
+            comment_tags: false,
+            foreign_servers: false,
+            view_lineage: false,
+            create_sql: false,
+            mock: None,
+            timeout: None,
+            metrics: None,
+            tunnel: Arc::default(),
+            supervisor: None,
+        })
+    }
+
+    /// 使用内存模拟后端创建服务，不需要真实数据库
+    pub fn from_mock(mock: MockMeta) -> Self {
+        Self {
+            connection: ConnConfig {
+                url: String::new(),
+                port: 0,
+                username: String::new(),
+                password: String::new(),
+                database: String::new(),
+                schema: None,
+                db_type: DbType::Mock,
+                transaction_pooling: false,
+                statement_timeout: None,
+                lock_timeout: None,
+                credentials: None,
+                socket: None,
+                application_name: None,
+                connect_options: Default::default(),
+                statement_cache_capacity: None,
+                slow_statement_threshold: None,
+                ssh_tunnel: None,
+                read_only: false,
+                ssl_mode: None,
+                ssl_root_cert: None,
+            },
+            type_mapper: None,
+            read_only: false,
+            fail_on_empty: false,
+            comment_tags: false,
+            foreign_servers: false,
+            view_lineage: false,
+            create_sql: false,
+            mock: Some(mock),
+            timeout: None,
+            metrics: None,
+            tunnel: Arc::default(),
+            supervisor: None,
+        }
+    }
+
+    /// 开启或关闭只读模式，默认取 [`ConnConfig::read_only`]。只读模式下 query/count 只允许单条 SELECT，
+    /// 拒绝建表、修改注释等写操作；PostgreSQL 和 MySQL 的会话同时设为只读，由数据库兜底拒绝写入
+    pub fn with_read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self.connection.read_only = read_only;
+        self
+    }
+
+    /// 没有采集到任何表和视图时返回 [`MetaError::EmptySchema`]，适用于监控等空结果多半意味着配置错误的场景
+    pub fn with_fail_on_empty(mut self, fail_on_empty: bool) -> Self {
+        self.fail_on_empty = fail_on_empty;
+        self
+    }
+
+    /// 采集时从表和字段注释中解析 `@pii`、`key=value` 等标签，写入 `tags`
+    pub fn with_comment_tags(mut self, comment_tags: bool) -> Self {
+        self.comment_tags = comment_tags;
+        self
+    }
+
+    /// 采集时同时采集外部服务器及配置了用户映射的角色，结果写入 `Metadata::foreign_servers`
+    pub fn with_foreign_servers(mut self, foreign_servers: bool) -> Self {
+        self.foreign_servers = foreign_servers;
+        self
+    }
+
+    /// 采集时解析视图定义，得出每个视图字段的来源字段，写入 `column_lineage`
+    pub fn with_view_lineage(mut self, view_lineage: bool) -> Self {
+        self.view_lineage = view_lineage;
+        self
+    }
+
+    /// 采集时逐个对象取回数据库给出的定义语句（MySQL 的 `SHOW CREATE TABLE/VIEW`），写入 `create_sql`；
+    /// 每个对象一次查询，最多同时执行 8 个，没有权限的对象为 None
+    pub fn with_create_sql(mut self, create_sql: bool) -> Self {
+        self.create_sql = create_sql;
+        self
+    }
+
+    /// 开启常驻模式：各次调用复用同一个连接池；调用因连接断开失败时丢弃连接池，
+    /// 下次调用按退避间隔重建，期间并发的调用等待同一次重建。适用于长期运行的服务，
+    /// 数据库重启后无需重新创建 `MetadataService`。当前状态见 [`MetadataService::status`]
+    pub fn with_supervision(mut self, options: SupervisorOptions) -> Self {
+        self.supervisor = Some(Supervisor::new(options));
+        self
+    }
+
+    /// 常驻模式下的连接状态，未开启常驻模式时为 [`ServiceStatus::NotConnected`]
+    pub fn status(&self) -> ServiceStatus {
+        self.supervisor
+            .as_ref()
+            .map_or(ServiceStatus::NotConnected, Supervisor::status)
+    }
+
+    /// 设置单次调用的超时时间，超时后返回 [`MetaError::QueryTimeout`]，
+    /// 作用于 `get_metadata`、`count`、`query` 和 `sample`
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// 注册指标接收器，记录每次后端调用的耗时、错误及获取连接的等待时间
+    pub fn with_metrics(mut self, sink: impl MetricsSink + 'static) -> Self {
+        self.metrics = Some(Metrics(Arc::new(sink)));
+        self
+    }
+
+    /// 注册自定义类型映射，如将 `uuid`、`jsonb`、`geometry` 映射为自定义类型
+    pub fn with_type_mapper<F>(mut self, mapper: F) -> Self
+    where
+        F: Fn(&str) -> Option<FieldTypeEnum> + Send + Sync + 'static,
+    {
+        self.type_mapper = Some(TypeMapper(Arc::new(mapper)));
+        self
+    }
+
+    /// 对表字段应用自定义类型映射
+    fn map_table_types(&self, tables: &mut [TableInfo]) {
+        if let Some(mapper) = &self.type_mapper {
+            tables.iter_mut().for_each(|t| mapper.apply(&mut t.columns));
+        }
+    }
+
+    /// 对视图字段应用自定义类型映射
+    fn map_view_types(&self, views: &mut [ViewsInfo]) {
+        if let Some(mapper) = &self.type_mapper {
+            views.iter_mut().for_each(|v| mapper.apply(&mut v.columns));
+        }
+    }
+
+    /// 连接正常但没有任何表和视图时，按配置报错，或记录警告并返回提示信息
+    fn check_empty(&self, tables: usize, views: usize) -> Result<Option<String>, MetaError> {
+        if tables > 0 || views > 0 {
+            return Ok(None);
+        }
+        let target = match &self.connection.schema {
+            Some(schema) => format!("{}.{schema}", self.connection.database),
+            None => self.connection.database.clone(),
+        };
+        if self.fail_on_empty {
+            return Err(MetaError::EmptySchema(target));
+        }
+        let warning = format!("数据库中没有任何表或视图: {target}");
+        log::warn!("{warning}");
+        Ok(Some(warning))
+    }
+
+    /// 常驻模式下返回复用的后端，否则新建
+    pub(crate) async fn create_metadata_handler(&self) -> Result<Arc<dyn MetaTrait>, MetaError> {
+        match &self.supervisor {
+            Some(supervisor) => supervisor.handler(|| self.open_handler()).await,
+            None => self.open_handler().await.map(Arc::from),
+        }
+    }
+
+    /// 新建后端，配置了指标接收器时包装上调用计时
+    async fn open_handler(&self) -> Result<Box<dyn MetaTrait>, MetaError> {
+        match &self.metrics {
+            Some(metrics) => metrics.connect(self.connect()).await,
+            None => self.connect().await,
+        }
     }
 
-    async fn create_metadata_handler(&self) -> Result<Box<dyn MetaTrait>, MetaError> {
-        match self.connection.db_type {
-            DbType::Postgresql => Ok(Box::new(PgMeta::new(&self.connection).await?)),
-            DbType::MySql => Ok(Box::new(MysqlMeta::new(&self.connection).await?)),
+    /// 按数据库类型创建后端
+    async fn connect(&self) -> Result<Box<dyn MetaTrait>, MetaError> {
+        let mut connection = self.connection.resolve().await?;
+        if let (Some(tunnel), DbType::Postgresql | DbType::MySql) =
+            (&connection.ssh_tunnel, &connection.db_type)
+        {
+            connection.port = self.open_tunnel(tunnel).await?.into();
+            connection.url = "127.0.0.1".to_string();
+        }
+        match &connection.db_type {
+            #[cfg(feature = "postgres")]
+            DbType::Postgresql => Ok(Box::new(PgMeta::new(&connection).await?)),
+            #[cfg(not(feature = "postgres"))]
+            DbType::Postgresql => Err(MetaError::InvalidArgument("未启用 postgres 特性".into())),
+            #[cfg(feature = "mysql")]
+            DbType::MySql => Ok(Box::new(MysqlMeta::new(&connection).await?)),
+            #[cfg(not(feature = "mysql"))]
+            DbType::MySql => Err(MetaError::InvalidArgument("未启用 mysql 特性".into())),
             DbType::MariaDb => Err(MetaError::InvalidArgument("暂不支持MariaDB".into())),
             DbType::Sqlite => Err(MetaError::InvalidArgument("暂不支持SQLite".into())),
+            #[cfg(feature = "clickhouse")]
+            DbType::ClickHouse => Ok(Box::new(ClickHouseMeta::new(&connection)?)),
+            #[cfg(not(feature = "clickhouse"))]
+            DbType::ClickHouse => Err(MetaError::InvalidArgument("未启用 clickhouse 特性".into())),
+            #[cfg(feature = "duckdb")]
+            DbType::DuckDb => Ok(Box::new(DuckDbMeta::new(&connection)?)),
+            #[cfg(not(feature = "duckdb"))]
+            DbType::DuckDb => Err(MetaError::InvalidArgument("未启用 duckdb 特性".into())),
+            DbType::Mock => match &self.mock {
+                Some(mock) => Ok(Box::new(mock.clone())),
+                None => Ok(Box::new(MockMeta::from_snapshot(&connection.database)?)),
+            },
+            DbType::Custom(name) => {
+                let factory = backends()
+                    .read()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .get(name)
+                    .cloned()
+                    .ok_or_else(|| {
+                        MetaError::InvalidArgument(format!("未注册的数据库类型: {name}"))
+                    })?;
+                factory(connection.clone()).await
+            }
         }
     }
 
+    /// 返回 SSH 隧道的本地端口，隧道尚未建立或 ssh 进程已退出时重新建立
+    async fn open_tunnel(&self, config: &SshTunnelConfig) -> Result<u16, MetaError> {
+        let mut tunnel = self.tunnel.lock().await;
+        if let Some(tunnel) = tunnel.as_mut()
+            && tunnel.is_alive()
+        {
+            return Ok(tunnel.local_port);
+        }
+        let remote_host = config.remote_host.as_ref().unwrap_or(&self.connection.url);
+        let remote_port = match config.remote_port {
+            Some(port) => port,
+            None => u16::try_from(self.connection.port).map_err(|_| {
+                MetaError::InvalidArgument(format!("端口无效: {}", self.connection.port))
+            })?,
+        };
+        let opened = SshTunnel::open(config, remote_host, remote_port).await?;
+        let local_port = opened.local_port;
+        *tunnel = Some(opened);
+        Ok(local_port)
+    }
+
+    /// 建立连接以检查配置是否可用，返回建立连接的耗时
+    pub async fn test_connection(&self) -> Result<Duration, MetaError> {
+        let start = Instant::now();
+        self.guard(self.create_metadata_handler(), None).await?;
+        Ok(start.elapsed())
+    }
+
     pub async fn get_metadata(&self) -> Result<Metadata, MetaError> {
-        // 在 get_metadata 方法中调用抽取的方法
+        self.guard(self.fetch_metadata(&|_| {}), None).await
+    }
+
+    /// 一次采集多个 schema（目前仅 PostgreSQL），结果中的表和视图带有各自的 schema
+    pub async fn get_metadata_for_schemas(&self, schemas: &[&str]) -> Result<Metadata, MetaError> {
+        let mut service = self.clone();
+        service.connection.schema = Some(schemas.join(","));
+        // 复用的连接池对应原来的 schema
+        service.supervisor = service.supervisor.as_ref().map(Supervisor::detached);
+        service.get_metadata().await
+    }
+
+    /// 可取消的元数据采集，`token` 被取消后立即返回 [`MetaError::Cancelled`]，
+    /// 正在执行的查询随连接一起被丢弃
+    pub async fn get_metadata_with_cancel(
+        &self,
+        token: CancellationToken,
+    ) -> Result<Metadata, MetaError> {
+        self.guard(self.fetch_metadata(&|_| {}), Some(&token)).await
+    }
+
+    /// 采集元数据并通过 `on_progress` 报告进度，供命令行或界面显示进度条
+    pub async fn get_metadata_with_progress<F>(&self, on_progress: F) -> Result<Metadata, MetaError>
+    where
+        F: Fn(Progress) + Sync,
+    {
+        self.guard(self.fetch_metadata(&on_progress), None).await
+    }
+
+    /// 并发采集多个连接的元数据，同时进行的采集不超过 `concurrency` 个，按输入顺序返回各连接的结果。
+    /// 单个连接失败（包括配置校验失败）不影响其它连接
+    pub async fn get_metadata_many(
+        configs: Vec<ConnConfig>,
+        concurrency: usize,
+    ) -> Vec<Result<Metadata, MetaError>> {
+        stream::iter(configs)
+            .map(|config| async move { MetadataService::new(config)?.get_metadata().await })
+            .buffered(concurrency.max(1))
+            .collect()
+            .await
+    }
+
+    /// 依次采集表、主键、索引、外键、字段和视图；后端支持时按批加载字段，每批完成后报告一次进度
+    async fn fetch_metadata(
+        &self,
+        on_progress: &(dyn Fn(Progress) + Sync),
+    ) -> Result<Metadata, MetaError> {
         let metadata_handler = self.create_metadata_handler().await?;
 
         let mut tables_info = metadata_handler.get_tables().await?;
+        on_progress(Progress::TablesDiscovered(tables_info.len()));
+        if let Some(markers) = metadata_handler.change_markers().await? {
+            for table in &mut tables_info {
+                table.change_marker = markers.get(&table_key(table)).cloned();
+            }
+        }
+        let tables_info = self
+            .load_tables(metadata_handler.as_ref(), tables_info, on_progress)
+            .await?;
+        let columns = tables_info.iter().map(|t| t.columns.len()).sum();
+        on_progress(Progress::ColumnsFetched(columns));
+
+        let views_info = self.load_views(metadata_handler.as_ref()).await?;
+        on_progress(Progress::ViewsDiscovered(views_info.len()));
+        let mut metadata = Metadata {
+            tables: tables_info,
+            views: views_info,
+            case_insensitive: metadata_handler.case_insensitive_identifiers(),
+            ..Default::default()
+        };
+        self.load_foreign(metadata_handler.as_ref(), &mut metadata)
+            .await?;
+        if self.create_sql {
+            self.load_create_sql(metadata_handler.as_ref(), &mut metadata)
+                .await?;
+        }
+        metadata.warnings.extend(self.check_empty(
+            metadata.tables.len() + metadata.foreign_tables.len(),
+            metadata.views.len(),
+        )?);
+        if self.comment_tags {
+            metadata.parse_comment_tags();
+        }
+        if self.view_lineage {
+            metadata.resolve_view_lineage(self.dialect());
+        }
+        metadata.sort();
+        Ok(metadata)
+    }
+
+    /// 为 `get_tables` 返回的表补充主键、索引和字段
+    async fn load_tables(
+        &self,
+        metadata_handler: &dyn MetaTrait,
+        mut tables_info: Vec<TableInfo>,
+        on_progress: &(dyn Fn(Progress) + Sync),
+    ) -> Result<Vec<TableInfo>, MetaError> {
         metadata_handler.set_primary_key(&mut tables_info).await?;
         metadata_handler.set_index_key(&mut tables_info).await?;
-        metadata_handler.set_columns(&mut tables_info).await?;
+        metadata_handler.set_foreign_keys(&mut tables_info).await?;
+        on_progress(Progress::KeysFetched);
 
+        let total = tables_info.len();
+        let batch_size = metadata_handler.column_batch_size().unwrap_or(total).max(1);
+        let mut pending = tables_info.into_iter().peekable();
+        let mut tables_info = Vec::with_capacity(total);
+        while pending.peek().is_some() {
+            let mut batch: Vec<TableInfo> = pending.by_ref().take(batch_size).collect();
+            metadata_handler.set_columns(&mut batch).await?;
+            tables_info.append(&mut batch);
+            on_progress(Progress::TablesProcessed {
+                done: tables_info.len(),
+                total,
+            });
+        }
+        self.map_table_types(&mut tables_info);
+        Ok(tables_info)
+    }
+
+    /// 采集视图及其字段
+    async fn load_views(
+        &self,
+        metadata_handler: &dyn MetaTrait,
+    ) -> Result<Vec<ViewsInfo>, MetaError> {
         let mut views_info = metadata_handler.get_views().await?;
         metadata_handler.set_view_columns(&mut views_info).await?;
-        Ok(Metadata {
-            tables: tables_info,
-            views: views_info,
+        self.map_view_types(&mut views_info);
+        Ok(views_info)
+    }
+
+    /// 为表和视图补充定义语句
+    async fn load_create_sql(
+        &self,
+        metadata_handler: &dyn MetaTrait,
+        metadata: &mut Metadata,
+    ) -> Result<(), MetaError> {
+        let tables = stream::iter(&metadata.tables)
+            .map(|t| metadata_handler.get_create_sql("TABLE", &t.schema, &t.table_name))
+            .buffered(CREATE_SQL_CONCURRENCY)
+            .try_collect::<Vec<_>>()
+            .await?;
+        for (table, create_sql) in metadata.tables.iter_mut().zip(tables) {
+            table.create_sql = create_sql;
+        }
+        let views = stream::iter(&metadata.views)
+            .map(|v| metadata_handler.get_create_sql("VIEW", &v.schema, &v.view_name))
+            .buffered(CREATE_SQL_CONCURRENCY)
+            .try_collect::<Vec<_>>()
+            .await?;
+        for (view, create_sql) in metadata.views.iter_mut().zip(views) {
+            view.create_sql = create_sql;
+        }
+        Ok(())
+    }
+
+    /// 采集外部表，开启 `with_foreign_servers` 时同时采集外部服务器
+    async fn load_foreign(
+        &self,
+        metadata_handler: &dyn MetaTrait,
+        metadata: &mut Metadata,
+    ) -> Result<(), MetaError> {
+        metadata.foreign_tables = metadata_handler.get_foreign_tables().await?;
+        if let Some(mapper) = &self.type_mapper {
+            for table in &mut metadata.foreign_tables {
+                mapper.apply(&mut table.columns);
+            }
+        }
+        if self.foreign_servers {
+            metadata.foreign_servers = metadata_handler.get_foreign_servers().await?;
+        }
+        Ok(())
+    }
+
+    /// 增量刷新：只重新采集变更标记与 `metadata` 中不同的表（包括新增的表），删除已不存在的表，
+    /// 其余表只更新注释。视图和外部表没有变更标记，每次都重新采集。
+    /// 后端不支持变更标记时退化为全量采集。
+    ///
+    /// 变更标记只覆盖表定义、字段、默认值、索引、外键和注释，以下变更不会让表被重新采集，结果可能过时：
+    /// - PostgreSQL：只修改检查约束或行级安全策略；
+    /// - MySQL：只修改检查约束；CREATE_TIME/UPDATE_TIME 的变化在
+    ///   information_schema_stats_expiry 缓存期内不可见，字段和索引的修改不受影响。
+    ///
+    /// 需要保证完整时使用 [`MetadataService::get_metadata`] 全量采集
+    pub async fn refresh(&self, metadata: &mut Metadata) -> Result<(), MetaError> {
+        let refreshed = async {
+            let metadata_handler = self.create_metadata_handler().await?;
+            let Some(markers) = metadata_handler.change_markers().await? else {
+                return self.fetch_metadata(&|_| {}).await;
+            };
+
+            let mut previous: HashMap<(String, String), TableInfo> = metadata
+                .tables
+                .iter()
+                .map(|t| (table_key(t), t.clone()))
+                .collect();
+            let mut order = Vec::new();
+            let mut changed = Vec::new();
+            for mut table in metadata_handler.get_tables().await? {
+                let key = table_key(&table);
+                table.change_marker = markers.get(&key).cloned();
+                match previous.get_mut(&key) {
+                    Some(old)
+                        if old.change_marker.is_some()
+                            && old.change_marker == table.change_marker =>
+                    {
+                        old.comment = table.comment;
+                        old.updated_at = table.updated_at;
+                    }
+                    _ => changed.push(table),
+                }
+                order.push(key);
+            }
+
+            let changed = self
+                .load_tables(metadata_handler.as_ref(), changed, &|_| {})
+                .await?;
+            previous.extend(changed.into_iter().map(|t| (table_key(&t), t)));
+            let tables = order
+                .iter()
+                .filter_map(|key| previous.remove(key))
+                .collect::<Vec<_>>();
+            let views = self.load_views(metadata_handler.as_ref()).await?;
+            let mut metadata = Metadata {
+                tables,
+                views,
+                case_insensitive: metadata_handler.case_insensitive_identifiers(),
+                ..Default::default()
+            };
+            self.load_foreign(metadata_handler.as_ref(), &mut metadata)
+                .await?;
+            if self.create_sql {
+                self.load_create_sql(metadata_handler.as_ref(), &mut metadata)
+                    .await?;
+            }
+            metadata.warnings.extend(self.check_empty(
+                metadata.tables.len() + metadata.foreign_tables.len(),
+                metadata.views.len(),
+            )?);
+            if self.comment_tags {
+                metadata.parse_comment_tags();
+            }
+            if self.view_lineage {
+                metadata.resolve_view_lineage(self.dialect());
+            }
+            metadata.sort();
+            Ok(metadata)
+        };
+        *metadata = self.guard(refreshed, None).await?;
+        Ok(())
+    }
+
+    /// 执行计数 SQL，只读模式下会先校验语句
+    pub async fn count(&self, sql: &str) -> Result<i64, MetaError> {
+        self.check_read_only(sql)?;
+        let count = async { self.create_metadata_handler().await?.count(sql).await };
+        self.guard(count, None).await
+    }
+
+    /// 执行查询 SQL，只读模式下会先校验语句
+    pub async fn query(&self, sql: &str) -> Result<Vec<Vec<String>>, MetaError> {
+        self.check_read_only(sql)?;
+        let query = async { self.create_metadata_handler().await?.query(sql).await };
+        self.guard(query, None).await
+    }
+
+    /// 从表中抽取最多 n 行样本数据，值按字段类型转换；`table` 的 schema 为空时按连接的默认 schema 查找
+    pub async fn sample(
+        &self,
+        table: &QualifiedName,
+        n: usize,
+        strategy: SampleStrategy,
+    ) -> Result<SampleRows, MetaError> {
+        let sample = async {
+            self.create_metadata_handler()
+                .await?
+                .sample(table, n, strategy)
+                .await
+        };
+        self.guard(sample, None).await
+    }
+
+    /// 列出服务器上的数据库，`include_system` 为 false 时过滤掉系统库，供界面在完整采集前选择
+    pub async fn list_databases(&self, include_system: bool) -> Result<Vec<String>, MetaError> {
+        let databases = async {
+            self.create_metadata_handler()
+                .await?
+                .list_databases(include_system)
+                .await
+        };
+        self.guard(databases, None).await
+    }
+
+    /// 列出当前数据库中的 schema，`include_system` 为 false 时过滤掉系统 schema
+    pub async fn list_schemas(&self, include_system: bool) -> Result<Vec<String>, MetaError> {
+        let schemas = async {
+            self.create_metadata_handler()
+                .await?
+                .list_schemas(include_system)
+                .await
+        };
+        self.guard(schemas, None).await
+    }
+
+    /// 查询当前 schema 中库、表和字段上的授权，供安全审计确认谁能读写哪些表
+    pub async fn get_privileges(&self) -> Result<Vec<Privilege>, MetaError> {
+        let privileges = async { self.create_metadata_handler().await?.get_privileges().await };
+        self.guard(privileges, None).await
+    }
+
+    /// 并发执行多条互不依赖的查询，并发数不超过 `concurrency`，按输入顺序返回各语句的结果。
+    /// 单条语句失败或超时不影响其它语句，只有建立连接失败时整体返回错误
+    pub async fn query_batch(
+        &self,
+        sqls: Vec<&str>,
+        concurrency: usize,
+    ) -> Result<Vec<Result<Vec<Vec<String>>, MetaError>>, MetaError> {
+        let handler = self.guard(self.create_metadata_handler(), None).await?;
+        let handler = &handler;
+        let results = stream::iter(sqls)
+            .map(|sql| async move {
+                self.check_read_only(sql)?;
+                self.guard(handler.query(sql), None).await
+            })
+            .buffered(concurrency.max(1))
+            .collect()
+            .await;
+        Ok(results)
+    }
+
+    /// 按页码分页执行查询，页码从 1 开始，`with_total` 为 true 时同时统计总行数。
+    /// 原查询应自带 ORDER BY，否则各页之间的顺序不确定
+    pub async fn query_page(
+        &self,
+        sql: &str,
+        page: u64,
+        page_size: u64,
+        with_total: bool,
+    ) -> Result<Page, MetaError> {
+        self.check_read_only(sql)?;
+        let query = async {
+            self.create_metadata_handler()
+                .await?
+                .query_page(sql, page, page_size, with_total)
+                .await
+        };
+        self.guard(query, None).await
+    }
+
+    /// 按键值（游标）分页执行查询，返回 `key` 列大于 `after` 的下一页，`after` 为 None 时取第一页。
+    /// 下一页以返回的 [`Page::next_key`] 作为 `after`；大偏移量时比按页码分页快得多
+    pub async fn query_page_after(
+        &self,
+        sql: &str,
+        key: &str,
+        after: Option<&str>,
+        page_size: u64,
+    ) -> Result<Page, MetaError> {
+        self.check_read_only(sql)?;
+        let query = async {
+            self.create_metadata_handler()
+                .await?
+                .query_page_after(sql, key, after, page_size)
+                .await
+        };
+        self.guard(query, None).await
+    }
+
+    /// 修改表注释，注释为空时清除；只读模式下禁止
+    pub async fn set_table_comment(
+        &self,
+        table: &QualifiedName,
+        comment: &str,
+    ) -> Result<(), MetaError> {
+        self.check_writable("修改表注释")?;
+        let update = async {
+            self.create_metadata_handler()
+                .await?
+                .set_table_comment(table, comment)
+                .await
+        };
+        self.guard(update, None).await
+    }
+
+    /// 修改字段注释，注释为空时清除；只读模式下禁止
+    pub async fn set_column_comment(
+        &self,
+        table: &QualifiedName,
+        column: &str,
+        comment: &str,
+    ) -> Result<(), MetaError> {
+        self.check_writable("修改字段注释")?;
+        let update = async {
+            self.create_metadata_handler()
+                .await?
+                .set_column_comment(table, column, comment)
+                .await
+        };
+        self.guard(update, None).await
+    }
+
+    /// 为一次调用加上超时和取消
+    pub(crate) async fn guard<T>(
+        &self,
+        operation: impl Future<Output = Result<T, MetaError>>,
+        token: Option<&CancellationToken>,
+    ) -> Result<T, MetaError> {
+        let operation = async {
+            match self.timeout {
+                Some(timeout) => tokio::time::timeout(timeout, operation)
+                    .await
+                    .map_err(|e| MetaError::QueryTimeout(e.into()))?,
+                None => operation.await,
+            }
+        };
+        let result = match token {
+            // 部分 tokio-util 版本的 run_until_cancelled 先轮询 operation，已取消的令牌拦不住立即完成的调用
+            Some(token) if token.is_cancelled() => Err(MetaError::Cancelled),
+            Some(token) => token
+                .run_until_cancelled(operation)
+                .await
+                .unwrap_or(Err(MetaError::Cancelled)),
+            None => operation.await,
+        };
+        if let (Err(e), Some(supervisor)) = (&result, &self.supervisor) {
+            supervisor.observe(e).await;
+        }
+        result
+    }
+
+    /// 连接对应的 SQL 方言
+    pub(crate) fn dialect(&self) -> Dialect {
+        Dialect::from(&self.connection.db_type)
+    }
+
+    /// 只读模式下拒绝所有写操作
+    pub(crate) fn check_writable(&self, operation: &str) -> Result<(), MetaError> {
+        if self.read_only {
+            return Err(MetaError::ReadOnlyViolation(operation.to_string()));
+        }
+        Ok(())
+    }
+
+    /// 只读模式下拒绝非 SELECT、多语句及危险函数
+    pub(crate) fn check_read_only(&self, sql: &str) -> Result<(), MetaError> {
+        if self.read_only {
+            sql_guard::validate_read_only(sql, self.dialect())?;
+        }
+        Ok(())
+    }
+
+    /// 以事件流的形式采集元数据，每个阶段完成后立即产出对应事件，出错后流结束
+    pub fn harvest(&self) -> impl Stream<Item = Result<HarvestEvent, MetaError>> + '_ {
+        self.harvest_until(None)
+    }
+
+    /// 可取消的事件流采集，`token` 被取消后流以 [`MetaError::Cancelled`] 结束
+    pub fn harvest_with_cancel(
+        &self,
+        token: CancellationToken,
+    ) -> impl Stream<Item = Result<HarvestEvent, MetaError>> + '_ {
+        self.harvest_until(Some(token))
+    }
+
+    /// 逐个阶段采集，每个阶段单独加上超时和取消
+    fn harvest_until(
+        &self,
+        token: Option<CancellationToken>,
+    ) -> impl Stream<Item = Result<HarvestEvent, MetaError>> + '_ {
+        let started = Instant::now();
+        stream::unfold(Some(HarvestPhase::Connect), move |phase| {
+            let token = token.clone();
+            async move {
+                let phase = phase?;
+                let step = self.harvest_phase(phase, started);
+                match self.guard(step, token.as_ref()).await {
+                    Ok((events, next)) => Some((Ok(events), next)),
+                    Err(e) => Some((Err(e), None)),
+                }
+            }
+        })
+        .flat_map(|step| match step {
+            Ok(events) => stream::iter(events.into_iter().map(Ok)).left_stream(),
+            Err(e) => stream::once(async { Err(e) }).right_stream(),
         })
     }
+
+    /// 执行一个采集阶段，返回该阶段产生的事件和下一阶段
+    async fn harvest_phase(
+        &self,
+        phase: HarvestPhase,
+        started: Instant,
+    ) -> Result<(Vec<HarvestEvent>, Option<HarvestPhase>), MetaError> {
+        match phase {
+            HarvestPhase::Connect => {
+                let handler = self.create_metadata_handler().await?;
+                let tables = handler.get_tables().await?;
+                let events = tables
+                    .iter()
+                    .cloned()
+                    .map(HarvestEvent::TableDiscovered)
+                    .collect();
+                Ok((events, Some(HarvestPhase::Keys(handler, tables))))
+            }
+            HarvestPhase::Keys(handler, mut tables) => {
+                handler.set_primary_key(&mut tables).await?;
+                handler.set_index_key(&mut tables).await?;
+                handler.set_foreign_keys(&mut tables).await?;
+                let events = tables
+                    .iter()
+                    .cloned()
+                    .map(HarvestEvent::IndexesLoaded)
+                    .collect();
+                Ok((events, Some(HarvestPhase::Columns(handler, tables))))
+            }
+            HarvestPhase::Columns(handler, mut tables) => {
+                handler.set_columns(&mut tables).await?;
+                self.map_table_types(&mut tables);
+                let summary = HarvestSummary {
+                    tables: tables.len(),
+                    columns: tables.iter().map(|t| t.columns.len()).sum(),
+                    statement_timeout: self.connection.statement_timeout,
+                    lock_timeout: self.connection.lock_timeout,
+                    ..Default::default()
+                };
+                let events = tables
+                    .into_iter()
+                    .map(HarvestEvent::ColumnsLoaded)
+                    .collect();
+                Ok((events, Some(HarvestPhase::Views(handler, summary))))
+            }
+            HarvestPhase::Views(handler, mut summary) => {
+                let mut views = handler.get_views().await?;
+                handler.set_view_columns(&mut views).await?;
+                self.map_view_types(&mut views);
+                summary.views = views.len();
+                summary.columns += views.iter().map(|v| v.columns.len()).sum::<usize>();
+                summary.elapsed = started.elapsed();
+                summary
+                    .warnings
+                    .extend(self.check_empty(summary.tables, summary.views)?);
+                let mut events: Vec<_> = views
+                    .into_iter()
+                    .map(HarvestEvent::ViewDiscovered)
+                    .collect();
+                events.push(HarvestEvent::Completed(summary));
+                Ok((events, None))
+            }
+        }
+    }
+}
+
+/// 事件流采集的阶段
+enum HarvestPhase {
+    Connect,
+    Keys(Arc<dyn MetaTrait>, Vec<TableInfo>),
+    Columns(Arc<dyn MetaTrait>, Vec<TableInfo>),
+    Views(Arc<dyn MetaTrait>, HarvestSummary),
 }
 
 type MetadataResult<T> = Result<T, MetaError>;
 
+/// 按 (schema, 表名) 对应表的键
+fn table_key(table: &TableInfo) -> (String, String) {
+    (table.schema.clone(), table.table_name.clone())
+}
+
+/// 按表名过滤字段的后端每批加载的表数
+pub(crate) const COLUMN_BATCH_SIZE: usize = 500;
+
+/// 开启 `with_create_sql` 时同时取回定义语句的对象数
+pub(crate) const CREATE_SQL_CONCURRENCY: usize = 8;
+
 #[async_trait]
 pub trait MetaTrait: Send + Sync {
     /// 获取表
@@ -64,6 +959,11 @@ pub trait MetaTrait: Send + Sync {
     /// 设置表的字段
     async fn set_columns(&self, tables: &mut Vec<TableInfo>) -> MetadataResult<()>;
 
+    /// 设置表的外键，不支持外键的数据库保持为空
+    async fn set_foreign_keys(&self, tables: &mut Vec<TableInfo>) -> MetadataResult<()> {
+        Ok(())
+    }
+
     /// 获取视图
     async fn get_views(&self) -> MetadataResult<Vec<ViewsInfo>>;
 
@@ -75,4 +975,143 @@ pub trait MetaTrait: Send + Sync {
 
     /// query
     async fn query(&self, sql: &str) -> MetadataResult<Vec<Vec<String>>>;
+
+    /// 标识符比较是否忽略大小写，如 MySQL 的 lower_case_table_names 非 0
+    fn case_insensitive_identifiers(&self) -> bool {
+        false
+    }
+
+    /// 流式执行查询，每批最多 `batch_size` 行，值按字段类型转换，空值为 [`Value::Null`]。
+    /// 默认实现一次取回全部结果作为一批，值都是文本、不区分空值，也没有字段名
+    fn query_stream<'a>(
+        &'a self,
+        sql: &'a str,
+        batch_size: usize,
+    ) -> BoxStream<'a, MetadataResult<SampleRows>> {
+        stream::once(async move {
+            let rows = self.query(sql).await?;
+            Ok(SampleRows {
+                columns: vec![],
+                rows: rows
+                    .into_iter()
+                    .map(|row| row.into_iter().map(Value::Text).collect())
+                    .collect(),
+            })
+        })
+        .boxed()
+    }
+
+    /// 生成分页、引用标识符时使用的 SQL 方言
+    fn dialect(&self) -> Dialect {
+        Dialect::Postgresql
+    }
+
+    /// 按页码分页执行查询，默认把原查询包装为子查询后追加 LIMIT / OFFSET，
+    /// 语法不同的后端（如 SQL Server 的 OFFSET FETCH）应覆盖此方法
+    async fn query_page(
+        &self,
+        sql: &str,
+        page: u64,
+        page_size: u64,
+        with_total: bool,
+    ) -> MetadataResult<Page> {
+        let rows = self.query(&page::offset_sql(sql, page, page_size)?).await?;
+        let mut result = page::offset_page(rows, page_size);
+        if with_total {
+            result.total = Some(self.count(&page::count_sql(sql)).await? as u64);
+        }
+        Ok(result)
+    }
+
+    /// 按键值分页执行查询，默认把原查询包装为子查询后按键过滤、排序并追加 LIMIT
+    async fn query_page_after(
+        &self,
+        sql: &str,
+        key: &str,
+        after: Option<&str>,
+        page_size: u64,
+    ) -> MetadataResult<Page> {
+        let sql = page::keyset_sql(sql, self.dialect(), key, after, page_size)?;
+        Ok(page::keyset_page(self.query(&sql).await?, page_size))
+    }
+
+    /// 按批加载字段时每批的表数，仅适用于按表名过滤字段的后端；None 表示一次加载全部
+    fn column_batch_size(&self) -> Option<usize> {
+        None
+    }
+
+    /// 每张表的变更标记，键为 (schema, 表名)，标记不同说明表结构或注释可能发生了变化；
+    /// None 表示不支持增量刷新
+    async fn change_markers(&self) -> MetadataResult<Option<HashMap<(String, String), String>>> {
+        Ok(None)
+    }
+
+    /// 列出服务器上的数据库
+    async fn list_databases(&self, include_system: bool) -> MetadataResult<Vec<String>> {
+        Err(MetaError::InvalidArgument(
+            "该数据库不支持列出数据库".into(),
+        ))
+    }
+
+    /// 列出当前数据库中的 schema
+    async fn list_schemas(&self, include_system: bool) -> MetadataResult<Vec<String>> {
+        Err(MetaError::InvalidArgument(
+            "该数据库不支持列出schema".into(),
+        ))
+    }
+
+    /// 查询库、表和字段上的授权
+    async fn get_privileges(&self) -> MetadataResult<Vec<Privilege>> {
+        Err(MetaError::InvalidArgument("该数据库不支持查询权限".into()))
+    }
+
+    /// 外部表及其字段，不支持外部表的数据库返回空
+    async fn get_foreign_tables(&self) -> MetadataResult<Vec<ForeignTable>> {
+        Ok(vec![])
+    }
+
+    /// 外部服务器及其用户映射，不支持外部表的数据库返回空
+    async fn get_foreign_servers(&self) -> MetadataResult<Vec<ForeignServer>> {
+        Ok(vec![])
+    }
+
+    /// 数据库给出的定义语句，`kind` 为 TABLE 或 VIEW；不提供或没有权限时返回 None
+    async fn get_create_sql(
+        &self,
+        kind: &str,
+        schema: &str,
+        name: &str,
+    ) -> MetadataResult<Option<String>> {
+        Ok(None)
+    }
+
+    /// 执行不返回结果的语句，如建表等 DDL
+    async fn execute(&self, sql: &str) -> MetadataResult<()> {
+        Err(MetaError::InvalidArgument("该数据库不支持执行语句".into()))
+    }
+
+    /// 修改表注释
+    async fn set_table_comment(&self, table: &QualifiedName, comment: &str) -> MetadataResult<()> {
+        Err(MetaError::InvalidArgument("该数据库不支持修改注释".into()))
+    }
+
+    /// 修改字段注释
+    async fn set_column_comment(
+        &self,
+        table: &QualifiedName,
+        column: &str,
+        comment: &str,
+    ) -> MetadataResult<()> {
+        Err(MetaError::InvalidArgument("该数据库不支持修改注释".into()))
+    }
+
+    /// 抽样表数据
+    async fn sample(
+        &self,
+        table: &QualifiedName,
+        n: usize,
+        strategy: SampleStrategy,
+    ) -> MetadataResult<SampleRows> {
+        Err(MetaError::InvalidArgument("该数据库不支持数据抽样".into()))
+    }
 }
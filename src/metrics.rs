@@ -0,0 +1,257 @@
+use std::collections::HashMap;
+use std::fmt::{self, Debug};
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+
+use crate::error::MetaError;
+use crate::ident::{Dialect, QualifiedName};
+use crate::meta::MetaTrait;
+use crate::modal::{ForeignServer, ForeignTable, Privilege, TableInfo, ViewsInfo};
+use crate::page::Page;
+use crate::sample::{SampleRows, SampleStrategy};
+
+/// 指标接收器，通过 [`MetadataService::with_metrics`](crate::meta::MetadataService::with_metrics)
+/// 注册后记录每次后端调用的耗时、错误和获取连接的等待时间，可对接 Prometheus、StatsD 等。
+/// 方法都有空的默认实现，按需实现即可
+#[allow(unused_variables)]
+pub trait MetricsSink: Send + Sync {
+    /// 一次后端调用结束（无论成功与否），`operation` 为方法名，如 `get_tables`、`query`
+    fn record_query(&self, operation: &'static str, elapsed: Duration) {}
+
+    /// 一次后端调用失败
+    fn record_error(&self, operation: &'static str, error: &MetaError) {}
+
+    /// 获取可用连接的等待时间，包括建立连接池和首个连接
+    fn record_acquire(&self, wait: Duration) {}
+}
+
+/// 已注册的指标接收器
+#[derive(Clone)]
+pub(crate) struct Metrics(pub(crate) Arc<dyn MetricsSink>);
+
+impl Debug for Metrics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Metrics")
+    }
+}
+
+impl Metrics {
+    /// 建立连接并记录等待时间，成功后为后端包装上调用计时
+    pub(crate) async fn connect<F>(&self, connect: F) -> Result<Box<dyn MetaTrait>, MetaError>
+    where
+        F: Future<Output = Result<Box<dyn MetaTrait>, MetaError>>,
+    {
+        let started = Instant::now();
+        let handler = connect.await;
+        self.0.record_acquire(started.elapsed());
+        match handler {
+            Ok(inner) => Ok(Box::new(MeteredMeta {
+                inner,
+                sink: self.0.clone(),
+            })),
+            Err(e) => {
+                self.0.record_error("connect", &e);
+                Err(e)
+            }
+        }
+    }
+}
+
+/// 为后端的每次调用计时的包装
+struct MeteredMeta {
+    // 实际的后端
+    inner: Box<dyn MetaTrait>,
+    // 指标接收器
+    sink: Arc<dyn MetricsSink>,
+}
+
+impl MeteredMeta {
+    async fn record<T, F>(&self, operation: &'static str, call: F) -> Result<T, MetaError>
+    where
+        F: Future<Output = Result<T, MetaError>>,
+    {
+        let started = Instant::now();
+        let result = call.await;
+        self.sink.record_query(operation, started.elapsed());
+        if let Err(e) = &result {
+            self.sink.record_error(operation, e);
+        }
+        result
+    }
+}
+
+#[async_trait]
+impl MetaTrait for MeteredMeta {
+    async fn get_tables(&self) -> Result<Vec<TableInfo>, MetaError> {
+        self.record("get_tables", self.inner.get_tables()).await
+    }
+
+    async fn set_primary_key(&self, tables: &mut Vec<TableInfo>) -> Result<(), MetaError> {
+        self.record("set_primary_key", self.inner.set_primary_key(tables))
+            .await
+    }
+
+    async fn set_index_key(&self, tables: &mut Vec<TableInfo>) -> Result<(), MetaError> {
+        self.record("set_index_key", self.inner.set_index_key(tables))
+            .await
+    }
+
+    async fn set_columns(&self, tables: &mut Vec<TableInfo>) -> Result<(), MetaError> {
+        self.record("set_columns", self.inner.set_columns(tables))
+            .await
+    }
+
+    async fn set_foreign_keys(&self, tables: &mut Vec<TableInfo>) -> Result<(), MetaError> {
+        self.record("set_foreign_keys", self.inner.set_foreign_keys(tables))
+            .await
+    }
+
+    async fn get_views(&self) -> Result<Vec<ViewsInfo>, MetaError> {
+        self.record("get_views", self.inner.get_views()).await
+    }
+
+    async fn set_view_columns(&self, views: &mut Vec<ViewsInfo>) -> Result<(), MetaError> {
+        self.record("set_view_columns", self.inner.set_view_columns(views))
+            .await
+    }
+
+    async fn count(&self, sql: &str) -> Result<i64, MetaError> {
+        self.record("count", self.inner.count(sql)).await
+    }
+
+    async fn query(&self, sql: &str) -> Result<Vec<Vec<String>>, MetaError> {
+        self.record("query", self.inner.query(sql)).await
+    }
+
+    fn case_insensitive_identifiers(&self) -> bool {
+        self.inner.case_insensitive_identifiers()
+    }
+
+    /// 流式查询在返回后才逐批执行，不计时
+    fn query_stream<'a>(
+        &'a self,
+        sql: &'a str,
+        batch_size: usize,
+    ) -> BoxStream<'a, Result<SampleRows, MetaError>> {
+        self.inner.query_stream(sql, batch_size)
+    }
+
+    fn dialect(&self) -> Dialect {
+        self.inner.dialect()
+    }
+
+    async fn query_page(
+        &self,
+        sql: &str,
+        page: u64,
+        page_size: u64,
+        with_total: bool,
+    ) -> Result<Page, MetaError> {
+        self.record(
+            "query_page",
+            self.inner.query_page(sql, page, page_size, with_total),
+        )
+        .await
+    }
+
+    async fn query_page_after(
+        &self,
+        sql: &str,
+        key: &str,
+        after: Option<&str>,
+        page_size: u64,
+    ) -> Result<Page, MetaError> {
+        self.record(
+            "query_page_after",
+            self.inner.query_page_after(sql, key, after, page_size),
+        )
+        .await
+    }
+
+    fn column_batch_size(&self) -> Option<usize> {
+        self.inner.column_batch_size()
+    }
+
+    async fn change_markers(&self) -> Result<Option<HashMap<(String, String), String>>, MetaError> {
+        self.record("change_markers", self.inner.change_markers())
+            .await
+    }
+
+    async fn list_databases(&self, include_system: bool) -> Result<Vec<String>, MetaError> {
+        self.record("list_databases", self.inner.list_databases(include_system))
+            .await
+    }
+
+    async fn list_schemas(&self, include_system: bool) -> Result<Vec<String>, MetaError> {
+        self.record("list_schemas", self.inner.list_schemas(include_system))
+            .await
+    }
+
+    async fn get_foreign_tables(&self) -> Result<Vec<ForeignTable>, MetaError> {
+        self.record("get_foreign_tables", self.inner.get_foreign_tables())
+            .await
+    }
+
+    async fn get_foreign_servers(&self) -> Result<Vec<ForeignServer>, MetaError> {
+        self.record("get_foreign_servers", self.inner.get_foreign_servers())
+            .await
+    }
+
+    async fn get_create_sql(
+        &self,
+        kind: &str,
+        schema: &str,
+        name: &str,
+    ) -> Result<Option<String>, MetaError> {
+        self.record("get_create_sql", self.inner.get_create_sql(kind, schema, name))
+            .await
+    }
+
+    async fn get_privileges(&self) -> Result<Vec<Privilege>, MetaError> {
+        self.record("get_privileges", self.inner.get_privileges())
+            .await
+    }
+
+    async fn execute(&self, sql: &str) -> Result<(), MetaError> {
+        self.record("execute", self.inner.execute(sql)).await
+    }
+
+    async fn set_table_comment(
+        &self,
+        table: &QualifiedName,
+        comment: &str,
+    ) -> Result<(), MetaError> {
+        self.record(
+            "set_table_comment",
+            self.inner.set_table_comment(table, comment),
+        )
+        .await
+    }
+
+    async fn set_column_comment(
+        &self,
+        table: &QualifiedName,
+        column: &str,
+        comment: &str,
+    ) -> Result<(), MetaError> {
+        self.record(
+            "set_column_comment",
+            self.inner.set_column_comment(table, column, comment),
+        )
+        .await
+    }
+
+    async fn sample(
+        &self,
+        table: &QualifiedName,
+        n: usize,
+        strategy: SampleStrategy,
+    ) -> Result<SampleRows, MetaError> {
+        self.record("sample", self.inner.sample(table, n, strategy))
+            .await
+    }
+}
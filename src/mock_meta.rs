@@ -0,0 +1,274 @@
+use std::collections::{BTreeSet, HashMap};
+use std::path::Path;
+use std::time::Duration;
+
+use async_trait::async_trait;
+
+use crate::error::MetaError;
+use crate::meta::MetaTrait;
+use crate::modal::{ForeignServer, ForeignTable, Metadata, Privilege, TableInfo, ViewsInfo};
+
+/// 内存模拟后端，从固定的元数据和查询结果中取数，供下游在没有数据库的环境下做单元测试
+#[derive(Debug, Clone, Default)]
+pub struct MockMeta {
+    // 作为采集结果的元数据
+    metadata: Metadata,
+    // 预置的查询结果，键为 SQL 原文
+    query_results: HashMap<String, Vec<Vec<String>>>,
+    // 预置的表变更标记，为空时不支持增量刷新
+    change_markers: HashMap<(String, String), String>,
+    // 预置的授权
+    privileges: Vec<Privilege>,
+    // 预置的数据库列表
+    databases: Vec<String>,
+    // 每次查询前的模拟耗时
+    query_delay: Option<Duration>,
+}
+
+/// 系统库和系统 schema，对应 MySQL 的系统库与 PostgreSQL 的 pg_ 开头的 schema、information_schema
+fn is_system(name: &str) -> bool {
+    let name = name.to_lowercase();
+    name.starts_with("pg_")
+        || ["information_schema", "mysql", "performance_schema", "sys"].contains(&name.as_str())
+}
+
+impl MockMeta {
+    pub fn new(metadata: Metadata) -> Self {
+        Self {
+            metadata,
+            query_results: HashMap::new(),
+            change_markers: HashMap::new(),
+            privileges: Vec::new(),
+            databases: Vec::new(),
+            query_delay: None,
+        }
+    }
+
+    /// 以 [`Metadata::save`] 保存的快照作为数据源
+    pub fn from_snapshot(path: impl AsRef<Path>) -> Result<Self, MetaError> {
+        Ok(Self::new(Metadata::load(path)?))
+    }
+
+    /// 预置一条查询的结果，`count` 取第一行第一列
+    pub fn with_query_result(mut self, sql: impl Into<String>, rows: Vec<Vec<String>>) -> Self {
+        self.query_results.insert(sql.into(), rows);
+        self
+    }
+
+    /// 预置一张表的变更标记
+    pub fn with_change_marker(
+        mut self,
+        schema: impl Into<String>,
+        table: impl Into<String>,
+        marker: impl Into<String>,
+    ) -> Self {
+        self.change_markers
+            .insert((schema.into(), table.into()), marker.into());
+        self
+    }
+
+    /// 预置 `get_privileges` 返回的授权
+    pub fn with_privileges(mut self, privileges: Vec<Privilege>) -> Self {
+        self.privileges = privileges;
+        self
+    }
+
+    /// 预置 `list_databases` 返回的数据库
+    pub fn with_databases(mut self, databases: Vec<String>) -> Self {
+        self.databases = databases;
+        self
+    }
+
+    /// 让 `count` 和 `query` 每次先等待一段时间，用于测试超时和并发
+    pub fn with_query_delay(mut self, delay: Duration) -> Self {
+        self.query_delay = Some(delay);
+        self
+    }
+
+    /// 按名称查找预置的表
+    fn find_table(&self, table: &TableInfo) -> Option<&TableInfo> {
+        self.metadata
+            .tables
+            .iter()
+            .find(|t| t.qualified_name() == table.qualified_name())
+    }
+
+    /// 按名称查找预置的视图
+    fn find_view(&self, view: &ViewsInfo) -> Option<&ViewsInfo> {
+        self.metadata
+            .views
+            .iter()
+            .find(|v| v.qualified_name() == view.qualified_name())
+    }
+
+    async fn query_result(&self, sql: &str) -> Result<&Vec<Vec<String>>, MetaError> {
+        if let Some(delay) = self.query_delay {
+            tokio::time::sleep(delay).await;
+        }
+        self.query_results
+            .get(sql)
+            .ok_or_else(|| MetaError::InvalidArgument(format!("未预置查询结果: {sql}")))
+    }
+}
+
+/// 与真实后端一样分阶段返回：先给出表名，再逐步补充主键、索引、外键和字段
+#[async_trait]
+impl MetaTrait for MockMeta {
+    async fn get_tables(&self) -> Result<Vec<TableInfo>, MetaError> {
+        let tables = self
+            .metadata
+            .tables
+            .iter()
+            .map(|t| TableInfo {
+                pk_name: String::new(),
+                pk_column: String::new(),
+                index_columns: vec![],
+                foreign_keys: vec![],
+                columns: vec![],
+                create_sql: None,
+                ..t.clone()
+            })
+            .collect();
+        Ok(tables)
+    }
+
+    async fn set_primary_key(&self, tables: &mut Vec<TableInfo>) -> Result<(), MetaError> {
+        for table in tables {
+            if let Some(fixture) = self.find_table(table) {
+                table.pk_name = fixture.pk_name.clone();
+                table.set_pk_column(fixture.pk_column.clone());
+            }
+        }
+        Ok(())
+    }
+
+    async fn set_index_key(&self, tables: &mut Vec<TableInfo>) -> Result<(), MetaError> {
+        for table in tables {
+            if let Some(fixture) = self.find_table(table) {
+                table.set_index_columns(fixture.index_columns.clone());
+            }
+        }
+        Ok(())
+    }
+
+    async fn set_foreign_keys(&self, tables: &mut Vec<TableInfo>) -> Result<(), MetaError> {
+        for table in tables {
+            if let Some(fixture) = self.find_table(table) {
+                table.set_foreign_keys(fixture.foreign_keys.clone());
+            }
+        }
+        Ok(())
+    }
+
+    async fn set_columns(&self, tables: &mut Vec<TableInfo>) -> Result<(), MetaError> {
+        for table in tables {
+            if let Some(fixture) = self.find_table(table) {
+                table.set_columns(fixture.columns.clone());
+            }
+        }
+        Ok(())
+    }
+
+    async fn get_views(&self) -> Result<Vec<ViewsInfo>, MetaError> {
+        let views = self
+            .metadata
+            .views
+            .iter()
+            .map(|v| ViewsInfo {
+                columns: vec![],
+                create_sql: None,
+                ..v.clone()
+            })
+            .collect();
+        Ok(views)
+    }
+
+    async fn set_view_columns(&self, views: &mut Vec<ViewsInfo>) -> Result<(), MetaError> {
+        for view in views {
+            if let Some(fixture) = self.find_view(view) {
+                view.set_columns(fixture.columns.clone());
+            }
+        }
+        Ok(())
+    }
+
+    async fn count(&self, sql: &str) -> Result<i64, MetaError> {
+        let rows = self.query_result(sql).await?;
+        let value = rows.first().and_then(|row| row.first());
+        value
+            .and_then(|v| v.parse().ok())
+            .ok_or_else(|| MetaError::InvalidArgument(format!("预置的计数结果无效: {sql}")))
+    }
+
+    async fn query(&self, sql: &str) -> Result<Vec<Vec<String>>, MetaError> {
+        self.query_result(sql).await.cloned()
+    }
+
+    fn case_insensitive_identifiers(&self) -> bool {
+        self.metadata.case_insensitive
+    }
+
+    async fn list_databases(&self, include_system: bool) -> Result<Vec<String>, MetaError> {
+        let mut databases: Vec<String> = self
+            .databases
+            .iter()
+            .filter(|db| include_system || !is_system(db))
+            .cloned()
+            .collect();
+        databases.sort();
+        Ok(databases)
+    }
+
+    /// 元数据中出现过的 schema
+    async fn list_schemas(&self, include_system: bool) -> Result<Vec<String>, MetaError> {
+        let tables = self.metadata.tables.iter().map(|t| &t.schema);
+        let views = self.metadata.views.iter().map(|v| &v.schema);
+        let schemas: BTreeSet<&String> = tables
+            .chain(views)
+            .filter(|schema| include_system || !is_system(schema))
+            .collect();
+        Ok(schemas.into_iter().cloned().collect())
+    }
+
+    async fn get_privileges(&self) -> Result<Vec<Privilege>, MetaError> {
+        Ok(self.privileges.clone())
+    }
+
+    async fn get_foreign_tables(&self) -> Result<Vec<ForeignTable>, MetaError> {
+        Ok(self.metadata.foreign_tables.clone())
+    }
+
+    async fn get_foreign_servers(&self) -> Result<Vec<ForeignServer>, MetaError> {
+        Ok(self.metadata.foreign_servers.clone())
+    }
+
+    async fn get_create_sql(
+        &self,
+        kind: &str,
+        schema: &str,
+        name: &str,
+    ) -> Result<Option<String>, MetaError> {
+        let create_sql = match kind {
+            "VIEW" => self
+                .metadata
+                .views
+                .iter()
+                .find(|v| v.schema == schema && v.view_name == name)
+                .and_then(|v| v.create_sql.clone()),
+            _ => self
+                .metadata
+                .tables
+                .iter()
+                .find(|t| t.schema == schema && t.table_name == name)
+                .and_then(|t| t.create_sql.clone()),
+        };
+        Ok(create_sql)
+    }
+
+    async fn change_markers(&self) -> Result<Option<HashMap<(String, String), String>>, MetaError> {
+        if self.change_markers.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(self.change_markers.clone()))
+    }
+}
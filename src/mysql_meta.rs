@@ -1,47 +1,99 @@
 use crate::error::MetaError;
-use crate::modal::{Column, ConnConfig, FieldTypeEnum, IndexInfo, TableInfo, ViewsInfo};
+use crate::ident::{Dialect, QualifiedName, qualified_name, quote_ident, quote_literal, quote_literal_list};
+use crate::modal::{Column, ConnConfig, FieldTypeEnum, ForeignKey, IndexInfo, Privilege, SslMode, TableInfo, ViewsInfo};
+use crate::sample::{SampleRows, SampleStrategy, Value};
 use async_trait::async_trait;
-use sqlx::mysql::MySqlPoolOptions;
-use sqlx::{MySql, Pool, Row};
-use std::collections::HashMap;
+use futures::stream::BoxStream;
+use futures::{StreamExt, TryStreamExt};
+use log::LevelFilter;
+use sqlx::mysql::{MySqlConnectOptions, MySqlPoolOptions, MySqlRow, MySqlSslMode};
+use sqlx::{Column as _, ConnectOptions, Executor, MySql, Pool, Row, TypeInfo};
+use std::collections::{HashMap, HashSet};
 use std::time::Duration;
 
-use super::meta::MetaTrait;
+use super::meta::{COLUMN_BATCH_SIZE, MetaTrait};
 
 #[derive(Debug, Clone)]
 pub struct MysqlMeta {
     pub(crate) pool: Pool<MySql>,
     pub(crate) conn_config: ConnConfig,
+    // 服务器的 lower_case_table_names 非 0 时表名不区分大小写
+    pub(crate) case_insensitive: bool,
 }
 
 impl MysqlMeta {
     pub(crate) async fn new(conn_config: &ConnConfig) -> Result<Self, MetaError> {
-        let url = format!(
-            "mysql://{user_name}:{password}@{host}:{port}/{dbname}",
-            user_name = conn_config.username,
-            password = conn_config.password,
-            host = conn_config.url,
-            port = conn_config.port,
-            dbname = conn_config.database
-        );
+        let port = u16::try_from(conn_config.port)
+            .map_err(|_| MetaError::InvalidArgument(format!("端口无效: {}", conn_config.port)))?;
+        let mut options = MySqlConnectOptions::new()
+            .host(&conn_config.url)
+            .port(port)
+            .username(&conn_config.username)
+            .password(&conn_config.password)
+            .database(&conn_config.database);
+        if let Some(socket) = &conn_config.socket {
+            options = options.socket(socket);
+        }
+        if let Some(capacity) = conn_config.statement_cache_capacity {
+            options = options.statement_cache_capacity(capacity);
+        }
+        if let Some(threshold) = conn_config.slow_statement_threshold {
+            options = options.log_slow_statements(LevelFilter::Warn, threshold);
+        }
+        if let Some(mode) = conn_config.ssl_mode {
+            options = options.ssl_mode(match mode {
+                SslMode::Disable => MySqlSslMode::Disabled,
+                SslMode::Prefer => MySqlSslMode::Preferred,
+                SslMode::Require => MySqlSslMode::Required,
+                SslMode::VerifyCa => MySqlSslMode::VerifyCa,
+                SslMode::VerifyFull => MySqlSslMode::VerifyIdentity,
+            });
+        }
+        if let Some(cert) = &conn_config.ssl_root_cert {
+            options = options.ssl_ca(cert);
+        }
+        let session_sql = session_settings(conn_config);
         let pool = MySqlPoolOptions::new()
             .max_connections(30)
             .min_connections(1)
             .acquire_timeout(Duration::from_secs(5))
-            .connect(&url)
+            .after_connect(move |conn, _| {
+                let session_sql = session_sql.clone();
+                Box::pin(async move {
+                    for sql in &session_sql {
+                        conn.execute(sql.as_str()).await?;
+                    }
+                    Ok(())
+                })
+            })
+            .connect_with(options)
             .await?;
+        let lower_case_table_names: String =
+            sqlx::query_scalar("SELECT CONVERT(@@lower_case_table_names,char)")
+                .fetch_one(&pool)
+                .await?;
         Ok(Self {
             pool,
             conn_config: conn_config.clone(),
+            case_insensitive: lower_case_table_names.trim() != "0",
         })
     }
 
+    /// 表名在映射中的键，不区分大小写时统一转为小写
+    fn name_key(&self, name: &str) -> String {
+        if self.case_insensitive {
+            name.to_lowercase()
+        } else {
+            name.to_string()
+        }
+    }
+
     async fn get_columns(
         &self,
         table_names: Vec<String>,
         pk_map: HashMap<String, String>,
     ) -> Result<HashMap<String, Vec<Column>>, MetaError> {
-        let tables_str = table_names.join("','");
+        let tables_str = quote_literal_list(&table_names, Dialect::MySql);
 
         let sql = format!(
             "SELECT CONVERT(TABLE_NAME,char), CONVERT(COLUMN_NAME,char), CONVERT(DATA_TYPE,char), CONVERT(COLUMN_TYPE,char),
@@ -49,18 +101,22 @@ impl MysqlMeta {
                     NUMERIC_SCALE,
                     IS_NULLABLE,
                     CONVERT(COLUMN_COMMENT,char),
-                    EXTRA
+                    EXTRA,
+                    NUMERIC_PRECISION,
+                    CONVERT(COLUMN_DEFAULT,char),
+                    ORDINAL_POSITION
              FROM information_schema.COLUMNS
-             WHERE TABLE_SCHEMA = '{schema}'
-               AND TABLE_NAME IN ('{tables_str}')",
-            schema = &self.conn_config.database
+             WHERE TABLE_SCHEMA = {schema}
+               AND TABLE_NAME IN ({tables_str})
+             ORDER BY TABLE_NAME, ORDINAL_POSITION",
+            schema = quote_literal(&self.conn_config.database, Dialect::MySql)
         );
 
         let rows = sqlx::query(&sql).fetch_all(&self.pool).await?;
         let mut column_map = HashMap::new();
 
         for row in rows {
-            let table: String = row.get(0);
+            let table = self.name_key(&row.get::<String, usize>(0));
             let column_name: String = row.get(1);
             let type_name: String = row.get(2);
 
@@ -76,13 +132,10 @@ impl MysqlMeta {
             let extra: Option<String> = row.get(8);
             let auto_increment = extra.as_ref().map(|x| x.to_lowercase() == "auto_increment");
 
-            // 处理列定义
-            let column_def: String = row.get(8);
-            let column_def = if column_def.is_empty() {
-                None
-            } else {
-                Some(column_def)
-            };
+            // 处理列定义，字符串字面量加上引号，与 PostgreSQL 的写法一致
+            let column_def = row
+                .get::<Option<String>, usize>(10)
+                .map(|default| default_sql(default, extra.as_deref().unwrap_or_default()));
 
             // 处理长度
             let length = row.get::<Option<i64>, usize>(4).unwrap_or(-1);
@@ -95,6 +148,12 @@ impl MysqlMeta {
                     None
                 };
 
+            // 定点数精度
+            let precision = row.get::<Option<u64>, usize>(9).unwrap_or_default();
+            // COLUMN_TYPE 带有 unsigned 等修饰，DATA_TYPE 没有
+            let column_type = FieldTypeEnum::mysql_field_type(row.get(3))
+                .with_precision(precision as i32, digit.map(|x| x as i32));
+
             // 检查是否为主键
             let is_pk = pk_map.get(&table) == Some(&column_name);
 
@@ -103,7 +162,7 @@ impl MysqlMeta {
                 .or_insert_with(Vec::new)
                 .push(Column {
                     name: column_name,
-                    column_type: FieldTypeEnum::mysql_field_type(row.get(2)),
+                    column_type,
                     type_name: row.get(2),
                     length: length as i32,
                     digit: digit.map(|x| x as i32),
@@ -112,45 +171,219 @@ impl MysqlMeta {
                     auto_increment,
                     column_def,
                     is_pk,
+                    ordinal_position: u32::try_from(row.get::<u64, usize>(11)).unwrap_or_default(),
+                    tags: Default::default(),
                 });
         }
         Ok(column_map)
     }
+
+    /// `SHOW CREATE TABLE` / `SHOW CREATE VIEW` 返回的定义语句，`kind` 为 TABLE 或 VIEW
+    async fn show_create(&self, kind: &str, schema: &str, name: &str) -> Result<String, MetaError> {
+        let sql = format!("SHOW CREATE {kind} {}", qualified_name(schema, name, Dialect::MySql));
+        let row = sqlx::query(&sql).fetch_one(&self.pool).await?;
+        // 部分版本以二进制字符串返回
+        match row.try_get::<String, usize>(1) {
+            Ok(create_sql) => Ok(create_sql),
+            Err(_) => Ok(String::from_utf8_lossy(&row.try_get::<Vec<u8>, usize>(1)?).into_owned()),
+        }
+    }
+
+    /// 从 information_schema 还原字段定义（不含注释），供 MODIFY COLUMN 使用；未限定 schema 时取连接的数据库
+    async fn column_definition(
+        &self,
+        table: &QualifiedName,
+        column: &str,
+    ) -> Result<String, MetaError> {
+        let schema = if table.schema.is_empty() {
+            &self.conn_config.database
+        } else {
+            &table.schema
+        };
+        let sql = format!(
+            "SELECT CONVERT(COLUMN_TYPE,char), CONVERT(COLLATION_NAME,char), IS_NULLABLE,
+                    CONVERT(COLUMN_DEFAULT,char), CONVERT(EXTRA,char), CONVERT(GENERATION_EXPRESSION,char)
+             FROM information_schema.COLUMNS
+             WHERE TABLE_SCHEMA = {schema} AND TABLE_NAME = {table} AND COLUMN_NAME = {column}",
+            schema = quote_literal(schema, Dialect::MySql),
+            table = quote_literal(&table.name, Dialect::MySql),
+            column = quote_literal(column, Dialect::MySql)
+        );
+        let row = sqlx::query(&sql)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_else(|| MetaError::InvalidArgument(format!("字段不存在: {table}.{column}")))?;
+
+        let column_type: String = row.get(0);
+        let collation: Option<String> = row.get(1);
+        let nullable: String = row.get(2);
+        let default: Option<String> = row.get(3);
+        let extra: String = row.get::<Option<String>, usize>(4).unwrap_or_default();
+        let generation: String = row.get::<Option<String>, usize>(5).unwrap_or_default();
+
+        let mut definition = vec![column_type];
+        if let Some(collation) = collation {
+            definition.push(format!("COLLATE {collation}"));
+        }
+        if !generation.is_empty() {
+            // 生成列：保留表达式和存储方式，不能带默认值
+            let storage = if extra.to_uppercase().contains("STORED") {
+                "STORED"
+            } else {
+                "VIRTUAL"
+            };
+            definition.push(format!("GENERATED ALWAYS AS ({generation}) {storage}"));
+        }
+        definition.push(if nullable == "YES" { "NULL" } else { "NOT NULL" }.to_string());
+        if generation.is_empty()
+            && let Some(default) = default
+        {
+            definition.push(format!("DEFAULT {}", default_sql(default, &extra)));
+        }
+        // EXTRA 中的 auto_increment、on update 等原样保留，标记类的关键字去掉
+        let extra = extra
+            .split_whitespace()
+            .filter(|word| {
+                !["DEFAULT_GENERATED", "VIRTUAL", "STORED", "GENERATED"]
+                    .contains(&word.to_uppercase().as_str())
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+        if !extra.is_empty() {
+            definition.push(extra);
+        }
+        Ok(definition.join(" "))
+    }
+}
+
+/// 系统库
+const SYSTEM_DATABASES: &[&str] = &["information_schema", "mysql", "performance_schema", "sys"];
+
+/// 建立连接后执行的会话设置语句
+pub(crate) fn session_settings(conn_config: &ConnConfig) -> Vec<String> {
+    let mut session_sql = Vec::new();
+    if let Some(timeout) = conn_config.statement_timeout {
+        // max_execution_time 只作用于 SELECT，单位毫秒
+        session_sql.push(format!(
+            "SET SESSION max_execution_time = {}",
+            timeout.as_millis()
+        ));
+    }
+    if let Some(timeout) = conn_config.lock_timeout {
+        // lock_wait_timeout 控制元数据锁等待，单位秒，最小为 1
+        session_sql.push(format!(
+            "SET SESSION lock_wait_timeout = {}",
+            timeout.as_secs().max(1)
+        ));
+    }
+    if conn_config.read_only {
+        session_sql.push("SET SESSION TRANSACTION READ ONLY".to_string());
+    }
+    // 自定义会话参数，参数名已在 ConnConfig::validate 中校验；十进制数原样传入，
+    // 其余（包括 NaN、inf 这类 f64 能解析的单词）按字符串处理
+    let mut custom: Vec<_> = conn_config.connect_options.iter().collect();
+    custom.sort();
+    for (name, value) in custom {
+        let numeric = !value.is_empty()
+            && value.parse::<f64>().is_ok()
+            && value
+                .chars()
+                .all(|c| c.is_ascii_digit() || matches!(c, '.' | '-'));
+        let value = if numeric {
+            value.clone()
+        } else {
+            quote_literal(value, Dialect::MySql)
+        };
+        session_sql.push(format!("SET SESSION {name} = {value}"));
+    }
+    session_sql
+}
+
+/// 把 information_schema 中的 COLUMN_DEFAULT 还原为 DDL 中的写法：
+/// 字面量不带引号，需要补上；表达式默认值（EXTRA 含 DEFAULT_GENERATED）加括号
+fn default_sql(default: String, extra: &str) -> String {
+    if default.to_uppercase().starts_with("CURRENT_TIMESTAMP") || default.starts_with("b'") {
+        default
+    } else if extra.to_uppercase().contains("DEFAULT_GENERATED") {
+        format!("({default})")
+    } else {
+        quote_literal(&default, Dialect::MySql)
+    }
+}
+
+/// 修改表注释的语句
+pub(crate) fn table_comment_sql(table: &QualifiedName, comment: &str) -> String {
+    format!(
+        "ALTER TABLE {} COMMENT = {}",
+        table.render_with_default(Dialect::MySql, None),
+        quote_literal(comment, Dialect::MySql)
+    )
+}
+
+/// 修改字段注释的语句，`definition` 为原字段定义（类型、可空、默认值等）
+pub(crate) fn column_comment_sql(
+    table: &QualifiedName,
+    column: &str,
+    definition: &str,
+    comment: &str,
+) -> String {
+    format!(
+        "ALTER TABLE {} MODIFY COLUMN {} {definition} COMMENT {}",
+        table.render_with_default(Dialect::MySql, None),
+        quote_ident(column, Dialect::MySql),
+        quote_literal(comment, Dialect::MySql)
+    )
 }
 
 #[async_trait]
 impl MetaTrait for MysqlMeta {
     async fn get_tables(&self) -> Result<Vec<TableInfo>, MetaError> {
         let sql = format!(
-            "SELECT CONVERT(TABLE_SCHEMA,char), CONVERT(TABLE_NAME,char), CONVERT(TABLE_COMMENT,char) FROM information_schema.TABLES WHERE TABLE_SCHEMA = '{db_name}' AND TABLE_TYPE = 'BASE TABLE'",
-            db_name = &self.conn_config.database
+            "SELECT CONVERT(TABLE_SCHEMA,char), CONVERT(TABLE_NAME,char), CONVERT(TABLE_COMMENT,char),
+                    DATE_FORMAT(CREATE_TIME, '%Y-%m-%d %H:%i:%s'), DATE_FORMAT(UPDATE_TIME, '%Y-%m-%d %H:%i:%s')
+             FROM information_schema.TABLES WHERE TABLE_SCHEMA = {db_name} AND TABLE_TYPE = 'BASE TABLE'",
+            db_name = quote_literal(&self.conn_config.database, Dialect::MySql)
         );
         let rows = sqlx::query(&sql)
             .map(|row: sqlx::mysql::MySqlRow| {
                 let schema = row.get(0);
                 let table_name = row.get(1);
                 let comment = row.get(2);
-                TableInfo::new(schema, table_name, Some(comment))
+                TableInfo {
+                    created_at: row.get(3),
+                    updated_at: row.get(4),
+                    ..TableInfo::new(schema, table_name, Some(comment))
+                }
             })
             .fetch_all(&self.pool)
             .await?;
         Ok(rows)
     }
 
+    fn case_insensitive_identifiers(&self) -> bool {
+        self.case_insensitive
+    }
+
+    fn dialect(&self) -> Dialect {
+        Dialect::MySql
+    }
+
     async fn set_primary_key(&self, table_vec: &mut Vec<TableInfo>) -> Result<(), MetaError> {
         let sql = format!(
             "SELECT CONVERT(TABLE_NAME,char), CONVERT(COLUMN_NAME,char)
             FROM INFORMATION_SCHEMA.`KEY_COLUMN_USAGE`
-            WHERE TABLE_SCHEMA = '{schema}' AND CONSTRAINT_NAME = 'PRIMARY'",
-            schema = &self.conn_config.database
+            WHERE TABLE_SCHEMA = {schema} AND CONSTRAINT_NAME = 'PRIMARY'",
+            schema = quote_literal(&self.conn_config.database, Dialect::MySql)
         );
 
         let rows = sqlx::query(&sql).fetch_all(&self.pool).await?;
-        let pk_map: HashMap<String, String> =
-            rows.iter().map(|row| (row.get(0), row.get(1))).collect();
+        let pk_map: HashMap<String, String> = rows
+            .iter()
+            .map(|row| (self.name_key(&row.get::<String, usize>(0)), row.get(1)))
+            .collect();
 
         for table in table_vec {
-            if let Some(name) = pk_map.get(&table.table_name) {
+            if let Some(name) = pk_map.get(&self.name_key(&table.table_name)) {
                 table.set_pk_column(name.clone())
             }
         }
@@ -161,39 +394,52 @@ impl MetaTrait for MysqlMeta {
     async fn set_index_key(&self, table_vec: &mut Vec<TableInfo>) -> Result<(), MetaError> {
         let sql = format!(
             "SELECT
-        CONVERT(a.TABLE_SCHEMA,char),
         CONVERT(a.TABLE_NAME,char),
-        CONVERT(a.index_name,char),
-        GROUP_CONCAT(a.column_name ORDER BY seq_in_index) AS `Columns`,
-        CONVERT(a.NON_UNIQUE, char ) as `is_unique`
+        CONVERT(a.INDEX_NAME,char),
+        CONVERT(a.COLUMN_NAME,char),
+        CONVERT(a.NON_UNIQUE,char),
+        CONVERT(a.INDEX_TYPE,char),
+        CONVERT(a.SUB_PART,char)
     FROM information_schema.statistics a
-    WHERE a.table_schema = '{schema}' AND index_name <> 'PRIMARY'
-    GROUP BY a.TABLE_SCHEMA, a.TABLE_NAME, a.index_name, a.NON_UNIQUE",
-            schema = self.conn_config.database
+    WHERE a.table_schema = {schema} AND a.index_name <> 'PRIMARY'
+    ORDER BY a.TABLE_NAME, a.INDEX_NAME, a.SEQ_IN_INDEX",
+            schema = quote_literal(&self.conn_config.database, Dialect::MySql)
         );
 
         let rows = sqlx::query(&sql).fetch_all(&self.pool).await?;
 
+        // 函数索引（MySQL 8.0.13+）的键为表达式，没有字段名；只按其余字段记录会得到更严格的唯一约束，
+        // 含表达式的索引整个跳过
+        let functional: HashSet<(String, String)> = rows
+            .iter()
+            .filter(|row| row.get::<Option<String>, usize>(2).is_none())
+            .map(|row| (row.get(0), row.get(1)))
+            .collect();
         let mut index_map: HashMap<String, Vec<IndexInfo>> = HashMap::new();
         for row in rows {
-            let is_unique = row.get::<String, usize>(4) == "0";
-            let column_name = row.get::<String, usize>(3);
-            let columns = column_name.split(",").collect::<Vec<&str>>();
-            for ele in columns {
-                index_map
-                    .entry(row.get(1))
-                    .or_insert_with(Vec::new)
-                    .push(IndexInfo {
-                        column_name: ele.to_string(),
-                        index_name: row.get(2),
-                        index_def: "".to_string(),
-                        is_unique: is_unique,
-                    });
+            let Some(column_name) = row.get::<Option<String>, usize>(2) else {
+                continue;
+            };
+            if functional.contains(&(row.get(0), row.get(1))) {
+                continue;
             }
+            index_map
+                .entry(self.name_key(&row.get::<String, usize>(0)))
+                .or_insert_with(Vec::new)
+                .push(IndexInfo {
+                    column_name,
+                    index_name: row.get(1),
+                    index_def: "".to_string(),
+                    is_unique: row.get::<String, usize>(3) == "0",
+                    index_type: Some(row.get(4)),
+                    sub_part: row
+                        .get::<Option<String>, usize>(5)
+                        .and_then(|s| s.parse().ok()),
+                });
         }
 
         for table in table_vec {
-            if let Some(indexes) = index_map.get(&table.table_name) {
+            if let Some(indexes) = index_map.get(&self.name_key(&table.table_name)) {
                 table.set_index_columns(indexes.clone());
             }
         }
@@ -201,19 +447,75 @@ impl MetaTrait for MysqlMeta {
         Ok(())
     }
 
+    /// 设置表的外键，引用其它库中表的外键同样记录，被引用表的库名在 referenced_schema 中
+    async fn set_foreign_keys(&self, table_vec: &mut Vec<TableInfo>) -> Result<(), MetaError> {
+        let sql = format!(
+            "SELECT
+        CONVERT(k.TABLE_NAME,char),
+        CONVERT(k.CONSTRAINT_NAME,char),
+        CONVERT(k.COLUMN_NAME,char),
+        CONVERT(k.REFERENCED_TABLE_SCHEMA,char),
+        CONVERT(k.REFERENCED_TABLE_NAME,char),
+        CONVERT(k.REFERENCED_COLUMN_NAME,char),
+        CONVERT(r.UPDATE_RULE,char),
+        CONVERT(r.DELETE_RULE,char)
+    FROM information_schema.KEY_COLUMN_USAGE k
+    JOIN information_schema.REFERENTIAL_CONSTRAINTS r
+        ON r.CONSTRAINT_SCHEMA = k.CONSTRAINT_SCHEMA AND r.TABLE_NAME = k.TABLE_NAME
+        AND r.CONSTRAINT_NAME = k.CONSTRAINT_NAME
+    WHERE k.TABLE_SCHEMA = {schema} AND k.REFERENCED_TABLE_NAME IS NOT NULL
+    ORDER BY k.TABLE_NAME, k.CONSTRAINT_NAME, k.ORDINAL_POSITION",
+            schema = quote_literal(&self.conn_config.database, Dialect::MySql)
+        );
+
+        let rows = sqlx::query(&sql).fetch_all(&self.pool).await?;
+
+        // 复合外键每个字段一行，按顺序合并到同一约束
+        let mut fk_map: HashMap<String, Vec<ForeignKey>> = HashMap::new();
+        for row in rows {
+            let foreign_keys = fk_map
+                .entry(self.name_key(&row.get::<String, usize>(0)))
+                .or_default();
+            let name: String = row.get(1);
+            match foreign_keys.last_mut() {
+                Some(fk) if fk.name == name => {
+                    fk.columns.push(row.get(2));
+                    fk.referenced_columns.push(row.get(5));
+                }
+                _ => foreign_keys.push(ForeignKey {
+                    name,
+                    columns: vec![row.get(2)],
+                    referenced_schema: row.get(3),
+                    referenced_table: row.get(4),
+                    referenced_columns: vec![row.get(5)],
+                    on_update: row.get(6),
+                    on_delete: row.get(7),
+                }),
+            }
+        }
+
+        for table in table_vec {
+            if let Some(foreign_keys) = fk_map.remove(&self.name_key(&table.table_name)) {
+                table.set_foreign_keys(foreign_keys);
+            }
+        }
+
+        Ok(())
+    }
+
     async fn set_columns(&self, table_vec: &mut Vec<TableInfo>) -> Result<(), MetaError> {
         let table_names = table_vec.iter().map(|x| x.table_name.clone()).collect();
 
         let pk_map: HashMap<String, String> = table_vec
             .iter()
             .filter(|t| !t.pk_column.is_empty())
-            .map(|t| (t.table_name.clone(), t.pk_column.clone()))
+            .map(|t| (self.name_key(&t.table_name), t.pk_column.clone()))
             .collect();
 
         let column_map = self.get_columns(table_names, pk_map).await?;
 
-        for table in table_vec {
-            if let Some(columns) = column_map.get(&table.table_name) {
+        for table in table_vec.iter_mut() {
+            if let Some(columns) = column_map.get(&self.name_key(&table.table_name)) {
                 table.set_columns(columns.clone());
             }
         }
@@ -223,19 +525,28 @@ impl MetaTrait for MysqlMeta {
 
     async fn get_views(&self) -> Result<Vec<ViewsInfo>, MetaError> {
         let sql = format!(
-            "SELECT CONVERT(TABLE_SCHEMA,char),
-                    CONVERT(TABLE_NAME,char),
-                    CONVERT(TABLE_COMMENT,char)
-             FROM information_schema.TABLES
-             WHERE TABLE_SCHEMA = '{schema}'
-               AND TABLE_TYPE = 'VIEW'",
-            schema = self.conn_config.database
+            "SELECT CONVERT(t.TABLE_SCHEMA,char),
+                    CONVERT(t.TABLE_NAME,char),
+                    CONVERT(t.TABLE_COMMENT,char),
+                    CONVERT(v.VIEW_DEFINITION,char)
+             FROM information_schema.TABLES t
+             LEFT JOIN information_schema.VIEWS v
+                    ON v.TABLE_SCHEMA = t.TABLE_SCHEMA AND v.TABLE_NAME = t.TABLE_NAME
+             WHERE t.TABLE_SCHEMA = {schema}
+               AND t.TABLE_TYPE = 'VIEW'",
+            schema = quote_literal(&self.conn_config.database, Dialect::MySql)
         );
 
         let rows = sqlx::query(&sql).fetch_all(&self.pool).await?;
         let views = rows
             .iter()
-            .map(|row| ViewsInfo::new(row.get(0), row.get(1)))
+            .map(|row| ViewsInfo {
+                // 没有 SHOW VIEW 权限时 VIEW_DEFINITION 为空串
+                definition: row
+                    .get::<Option<String>, usize>(3)
+                    .filter(|d| !d.is_empty()),
+                ..ViewsInfo::new(row.get(0), row.get(1))
+            })
             .collect();
         Ok(views)
     }
@@ -244,8 +555,8 @@ impl MetaTrait for MysqlMeta {
         let view_names = view_vec.iter().map(|x| x.view_name.clone()).collect();
         let column_map = self.get_columns(view_names, HashMap::new()).await?;
 
-        for view in view_vec {
-            if let Some(columns) = column_map.get(&view.view_name) {
+        for view in view_vec.iter_mut() {
+            if let Some(columns) = column_map.get(&self.name_key(&view.view_name)) {
                 view.set_columns(columns.clone());
             }
         }
@@ -269,4 +580,189 @@ impl MetaTrait for MysqlMeta {
 
         Ok(rows)
     }
+
+    /// 字段查询按表名过滤，分批加载以便报告进度并限制 IN 列表长度
+    fn column_batch_size(&self) -> Option<usize> {
+        Some(COLUMN_BATCH_SIZE)
+    }
+
+    /// 以 CREATE_TIME、UPDATE_TIME 加上字段、索引和外键定义的校验和作为变更标记。
+    /// InnoDB 重建表的 DDL 会更新 CREATE_TIME，不重建表的 DDL（如 INSTANT 加列、只改注释）由校验和反映；
+    /// MySQL 8 中 CREATE_TIME 和 UPDATE_TIME 受 information_schema_stats_expiry 缓存影响。
+    /// 校验和用 SUM(CRC32(..)) 而不是 GROUP_CONCAT，避免受 group_concat_max_len 截断
+    async fn change_markers(&self) -> Result<Option<HashMap<(String, String), String>>, MetaError> {
+        let database = quote_literal(&self.conn_config.database, Dialect::MySql);
+        let sql = format!(
+            "SELECT CONVERT(t.TABLE_SCHEMA,char), CONVERT(t.TABLE_NAME,char),
+       CONVERT(CONCAT_WS('/', t.CREATE_TIME, t.UPDATE_TIME, c.checksum, s.checksum, f.checksum),char)
+FROM information_schema.TABLES t
+LEFT JOIN (SELECT TABLE_NAME, CONCAT(COUNT(*), ':', SUM(CRC32(CONCAT_WS(':', ORDINAL_POSITION, COLUMN_NAME, COLUMN_TYPE,
+               IS_NULLABLE, QUOTE(COLUMN_DEFAULT), EXTRA, COLUMN_COMMENT)))) AS checksum
+           FROM information_schema.COLUMNS WHERE TABLE_SCHEMA = {database} GROUP BY TABLE_NAME) c
+  ON c.TABLE_NAME = t.TABLE_NAME
+LEFT JOIN (SELECT TABLE_NAME, CONCAT(COUNT(*), ':', SUM(CRC32(CONCAT_WS(':', INDEX_NAME, NON_UNIQUE, SEQ_IN_INDEX,
+               COLUMN_NAME, INDEX_TYPE)))) AS checksum
+           FROM information_schema.STATISTICS WHERE TABLE_SCHEMA = {database} GROUP BY TABLE_NAME) s
+  ON s.TABLE_NAME = t.TABLE_NAME
+LEFT JOIN (SELECT TABLE_NAME, CONCAT(COUNT(*), ':', SUM(CRC32(CONCAT_WS(':', CONSTRAINT_NAME, ORDINAL_POSITION,
+               COLUMN_NAME, REFERENCED_TABLE_SCHEMA, REFERENCED_TABLE_NAME, REFERENCED_COLUMN_NAME)))) AS checksum
+           FROM information_schema.KEY_COLUMN_USAGE
+           WHERE TABLE_SCHEMA = {database} AND REFERENCED_TABLE_NAME IS NOT NULL GROUP BY TABLE_NAME) f
+  ON f.TABLE_NAME = t.TABLE_NAME
+WHERE t.TABLE_SCHEMA = {database} AND t.TABLE_TYPE = 'BASE TABLE'"
+        );
+        let rows = sqlx::query(&sql).fetch_all(&self.pool).await?;
+        let markers = rows
+            .iter()
+            .map(|row| ((row.get(0), row.get(1)), row.get(2)))
+            .collect();
+        Ok(Some(markers))
+    }
+
+    /// 列出数据库，information_schema、mysql、performance_schema、sys 视为系统库
+    async fn list_databases(&self, include_system: bool) -> Result<Vec<String>, MetaError> {
+        let databases: Vec<String> = sqlx::query_scalar(
+            "SELECT CONVERT(SCHEMA_NAME,char) FROM information_schema.SCHEMATA ORDER BY SCHEMA_NAME",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(databases
+            .into_iter()
+            .filter(|db| {
+                include_system || !SYSTEM_DATABASES.contains(&db.to_lowercase().as_str())
+            })
+            .collect())
+    }
+
+    /// MySQL 中 schema 即数据库
+    async fn list_schemas(&self, include_system: bool) -> Result<Vec<String>, MetaError> {
+        self.list_databases(include_system).await
+    }
+
+    /// 没有 SHOW VIEW 等权限的对象返回 None，不影响其它对象
+    async fn get_create_sql(
+        &self,
+        kind: &str,
+        schema: &str,
+        name: &str,
+    ) -> Result<Option<String>, MetaError> {
+        match self.show_create(kind, schema, name).await {
+            Ok(create_sql) => Ok(Some(create_sql)),
+            Err(MetaError::PermissionDenied(e)) => {
+                log::warn!("没有权限查看 {schema}.{name} 的定义: {e}");
+                Ok(None)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// 库级、表级和字段级授权，来自 information_schema 中的 *_PRIVILEGES 视图。
+    /// 全局授权（如 root 的 ALL PRIVILEGES）不包含在内；当前用户没有 mysql 库的查询权限时只能看到自己的授权
+    async fn get_privileges(&self) -> Result<Vec<Privilege>, MetaError> {
+        let sql = format!(
+            "SELECT CONVERT(TABLE_SCHEMA,char), CAST(NULL AS CHAR), CAST(NULL AS CHAR), CONVERT(GRANTEE,char), CONVERT(PRIVILEGE_TYPE,char), CONVERT(IS_GRANTABLE,char)
+FROM information_schema.SCHEMA_PRIVILEGES WHERE TABLE_SCHEMA = {db_name}
+UNION ALL
+SELECT CONVERT(TABLE_SCHEMA,char), CONVERT(TABLE_NAME,char), CAST(NULL AS CHAR), CONVERT(GRANTEE,char), CONVERT(PRIVILEGE_TYPE,char), CONVERT(IS_GRANTABLE,char)
+FROM information_schema.TABLE_PRIVILEGES WHERE TABLE_SCHEMA = {db_name}
+UNION ALL
+SELECT CONVERT(TABLE_SCHEMA,char), CONVERT(TABLE_NAME,char), CONVERT(COLUMN_NAME,char), CONVERT(GRANTEE,char), CONVERT(PRIVILEGE_TYPE,char), CONVERT(IS_GRANTABLE,char)
+FROM information_schema.COLUMN_PRIVILEGES WHERE TABLE_SCHEMA = {db_name}
+ORDER BY 1, 2, 3, 4, 5",
+            db_name = quote_literal(&self.conn_config.database, Dialect::MySql)
+        );
+        let rows = sqlx::query(&sql).fetch_all(&self.pool).await?;
+        Ok(rows
+            .iter()
+            .map(|row| Privilege {
+                schema: row.get(0),
+                table: row.get(1),
+                column: row.get(2),
+                grantee: row.get(3),
+                privilege: row.get(4),
+                grantable: row.get::<String, usize>(5) == "YES",
+            })
+            .collect())
+    }
+
+    /// 执行不返回结果的语句；部分 DDL 不支持预处理协议，使用文本协议执行
+    async fn execute(&self, sql: &str) -> Result<(), MetaError> {
+        sqlx::raw_sql(sql).execute(&self.pool).await?;
+        Ok(())
+    }
+
+    /// 修改表注释
+    async fn set_table_comment(
+        &self,
+        table: &QualifiedName,
+        comment: &str,
+    ) -> Result<(), MetaError> {
+        self.execute(&table_comment_sql(table, comment)).await
+    }
+
+    /// 修改字段注释；MySQL 只能通过 MODIFY COLUMN 改注释，需带上完整的原字段定义
+    async fn set_column_comment(
+        &self,
+        table: &QualifiedName,
+        column: &str,
+        comment: &str,
+    ) -> Result<(), MetaError> {
+        let definition = self.column_definition(table, column).await?;
+        self.execute(&column_comment_sql(table, column, &definition, comment))
+            .await
+    }
+
+    /// 抽样表数据；MySQL 没有 TABLESAMPLE，随机抽样使用 ORDER BY RAND()，大表上开销较高
+    async fn sample(
+        &self,
+        table: &QualifiedName,
+        n: usize,
+        strategy: SampleStrategy,
+    ) -> Result<SampleRows, MetaError> {
+        let table = table.render_with_default(Dialect::MySql, None);
+        let sql = match strategy {
+            SampleStrategy::First => format!("SELECT * FROM {table} LIMIT {n}"),
+            SampleStrategy::Random => format!("SELECT * FROM {table} ORDER BY RAND() LIMIT {n}"),
+        };
+
+        let rows = sqlx::raw_sql(&sql).fetch_all(&self.pool).await?;
+        to_sample_rows(&rows)
+    }
+
+    /// 使用文本协议流式读取，按批返回
+    fn query_stream<'a>(
+        &'a self,
+        sql: &'a str,
+        batch_size: usize,
+    ) -> BoxStream<'a, Result<SampleRows, MetaError>> {
+        sqlx::raw_sql(sql)
+            .fetch(&self.pool)
+            .try_chunks(batch_size.max(1))
+            .map(|rows| to_sample_rows(&rows.map_err(|e| e.1)?))
+            .boxed()
+    }
+}
+
+/// 文本协议下所有值都以字节返回，按字段类型转换
+fn to_sample_rows(rows: &[MySqlRow]) -> Result<SampleRows, MetaError> {
+    let columns = rows
+        .first()
+        .map(|row| row.columns().iter().map(|c| c.name().to_string()).collect())
+        .unwrap_or_default();
+    let rows = rows
+        .iter()
+        .map(|row| {
+            row.columns()
+                .iter()
+                .map(|column| {
+                    let bytes: Option<&[u8]> = row.try_get_unchecked(column.ordinal())?;
+                    Ok(bytes.map_or(Value::Null, |bytes| {
+                        Value::from_bytes(bytes, column.type_info().name())
+                    }))
+                })
+                .collect()
+        })
+        .collect::<Result<_, MetaError>>()?;
+
+    Ok(SampleRows { columns, rows })
 }
@@ -0,0 +1,93 @@
+use serde::{Deserialize, Serialize};
+
+use crate::error::MetaError;
+use crate::ident::{Dialect, quote_ident, quote_literal};
+
+/// 分页查询时原查询作为子查询的别名
+const ALIAS: &str = "_page";
+
+/// 一页查询结果
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Page {
+    // 本页的行
+    pub rows: Vec<Vec<String>>,
+    // 原查询的总行数，未要求统计时为 None
+    pub total: Option<u64>,
+    // 之后是否还有数据
+    pub has_more: bool,
+    // 游标分页时本页最后一行的键值，作为下一页的起点；按页码分页或没有更多数据时为 None
+    pub next_key: Option<String>,
+}
+
+/// 去掉首尾空白和末尾的分号，以便作为子查询
+fn strip(sql: &str) -> &str {
+    sql.trim().trim_end_matches(';').trim_end()
+}
+
+/// 按页码分页的 SQL，页码从 1 开始；多取一行用于判断是否还有下一页
+pub(crate) fn offset_sql(sql: &str, page: u64, page_size: u64) -> Result<String, MetaError> {
+    if page == 0 || page_size == 0 {
+        return Err(MetaError::InvalidArgument(
+            "页码和每页行数必须大于 0".into(),
+        ));
+    }
+    let offset = (page - 1)
+        .checked_mul(page_size)
+        .ok_or_else(|| MetaError::InvalidArgument("页码超出范围".into()))?;
+    Ok(format!(
+        "SELECT * FROM ({}) AS {ALIAS} LIMIT {} OFFSET {offset}",
+        strip(sql),
+        page_size + 1
+    ))
+}
+
+/// 统计原查询总行数的 SQL
+pub(crate) fn count_sql(sql: &str) -> String {
+    format!("SELECT COUNT(*) FROM ({}) AS {ALIAS}", strip(sql))
+}
+
+/// 按键值分页的 SQL：取键值大于 `after` 的行，按键升序排列；键值额外作为最后一列返回。
+/// 键应当唯一且非空，否则可能漏行
+pub(crate) fn keyset_sql(
+    sql: &str,
+    dialect: Dialect,
+    key: &str,
+    after: Option<&str>,
+    page_size: u64,
+) -> Result<String, MetaError> {
+    if page_size == 0 {
+        return Err(MetaError::InvalidArgument("每页行数必须大于 0".into()));
+    }
+    let key = format!("{ALIAS}.{}", quote_ident(key, dialect));
+    let filter = match after {
+        Some(after) => format!(" WHERE {key} > {}", quote_literal(after, dialect)),
+        None => String::new(),
+    };
+    Ok(format!(
+        "SELECT {ALIAS}.*, {key} FROM ({}) AS {ALIAS}{filter} ORDER BY {key} LIMIT {}",
+        strip(sql),
+        page_size + 1
+    ))
+}
+
+/// 由多取一行的结果组装一页
+pub(crate) fn offset_page(mut rows: Vec<Vec<String>>, page_size: u64) -> Page {
+    let has_more = rows.len() as u64 > page_size;
+    rows.truncate(page_size as usize);
+    Page {
+        rows,
+        total: None,
+        has_more,
+        next_key: None,
+    }
+}
+
+/// 由多取一行、末列为键值的结果组装一页
+pub(crate) fn keyset_page(rows: Vec<Vec<String>>, page_size: u64) -> Page {
+    let mut page = offset_page(rows, page_size);
+    let keys: Vec<Option<String>> = page.rows.iter_mut().map(|row| row.pop()).collect();
+    if page.has_more {
+        page.next_key = keys.into_iter().last().flatten();
+    }
+    page
+}
@@ -1,11 +1,19 @@
 use crate::error::MetaError;
-use crate::modal::{Column, ConnConfig, FieldTypeEnum, IndexInfo, TableInfo, ViewsInfo};
-
-use super::meta::MetaTrait;
+use crate::ident::{Dialect, QualifiedName, quote_ident, quote_literal, quote_literal_list};
+use crate::modal::{
+    Column, ConnConfig, FieldTypeEnum, ForeignKey, ForeignServer, ForeignTable, IndexInfo, Policy,
+    Privilege, SslMode, TableInfo, ViewsInfo,
+};
+use crate::sample::{SampleRows, SampleStrategy, Value};
+
+use super::meta::{COLUMN_BATCH_SIZE, MetaTrait};
 use async_trait::async_trait;
-use sqlx::postgres::PgPoolOptions;
-use sqlx::{Pool, Postgres, Row};
-use std::collections::HashMap;
+use futures::stream::{self, BoxStream};
+use futures::{StreamExt, TryStreamExt};
+use sqlx::postgres::{PgConnectOptions, PgPoolOptions, PgRow, PgSslMode};
+use log::LevelFilter;
+use sqlx::{Column as _, ConnectOptions, Executor, Pool, Postgres, Row, TypeInfo};
+use std::collections::{BTreeMap, HashMap};
 use std::time::Duration;
 
 /// PostgreSQL元数据操作结构体
@@ -13,29 +21,255 @@ use std::time::Duration;
 pub struct PgMeta {
     /// PostgreSQL连接池
     pub(crate) pool: Pool<Postgres>,
+    /// 是否经由事务级连接池（如 pgbouncer）访问
+    transaction_pooling: bool,
+    /// 事务级连接池下每个事务开头执行的 SET LOCAL 语句，见 [`local_settings`]
+    local_settings: Option<String>,
+    /// 采集的 schema，来自 ConnConfig.schema（逗号分隔），默认为 public
+    schemas: Vec<String>,
 }
 
 impl PgMeta {
     /// 创建PgMeta实例
     pub async fn new(conn_config: &ConnConfig) -> Result<Self, MetaError> {
-        let url = format!(
-            "postgres://{user_name}:{password}@{host}:{port}/{dbname}",
-            user_name = conn_config.username,
-            password = conn_config.password,
-            host = conn_config.url,
-            port = conn_config.port,
-            dbname = conn_config.database
-        );
+        let port = u16::try_from(conn_config.port)
+            .map_err(|_| MetaError::InvalidArgument(format!("端口无效: {}", conn_config.port)))?;
+        let mut options = PgConnectOptions::new()
+            .host(&conn_config.url)
+            .port(port)
+            .username(&conn_config.username)
+            .password(&conn_config.password)
+            .database(&conn_config.database);
+        if let Some(socket) = &conn_config.socket {
+            options = options.socket(socket);
+        }
+        if let Some(name) = &conn_config.application_name {
+            options = options.application_name(name);
+        }
+        if let Some(mode) = conn_config.ssl_mode {
+            options = options.ssl_mode(match mode {
+                SslMode::Disable => PgSslMode::Disable,
+                SslMode::Prefer => PgSslMode::Prefer,
+                SslMode::Require => PgSslMode::Require,
+                SslMode::VerifyCa => PgSslMode::VerifyCa,
+                SslMode::VerifyFull => PgSslMode::VerifyFull,
+            });
+        }
+        if let Some(cert) = &conn_config.ssl_root_cert {
+            options = options.ssl_root_cert(cert);
+        }
+        if let Some(capacity) = conn_config.statement_cache_capacity {
+            options = options.statement_cache_capacity(capacity);
+        }
+        if let Some(threshold) = conn_config.slow_statement_threshold {
+            options = options.log_slow_statements(LevelFilter::Warn, threshold);
+        }
+        if conn_config.transaction_pooling {
+            // 事务级连接池下连接会在事务间切换，缓存的预编译语句会失效
+            options = options.statement_cache_capacity(0);
+        }
+        options = options.options(startup_options(conn_config));
 
         let pool = PgPoolOptions::new()
             .max_connections(30)
             .min_connections(1)
             .acquire_timeout(Duration::from_secs(10))
-            .connect(&url)
+            .connect_with(options)
             .await?;
 
-        Ok(Self { pool })
+        Ok(Self {
+            pool,
+            transaction_pooling: conn_config.transaction_pooling,
+            local_settings: local_settings(conn_config),
+            schemas: configured_schemas(conn_config.schema.as_deref()),
+        })
+    }
+
+    /// 按 (schema, 表名) 分组的行级安全策略
+    async fn get_policies(&self) -> Result<HashMap<(String, String), Vec<Policy>>, MetaError> {
+        let sql = format!(
+            "SELECT schemaname::text, tablename::text, policyname::text, cmd, permissive = 'PERMISSIVE',
+       roles::text[], qual, with_check
+FROM pg_catalog.pg_policies
+WHERE schemaname IN ({})
+ORDER BY schemaname, tablename, policyname",
+            self.schema_list()
+        );
+        let mut policies: HashMap<(String, String), Vec<Policy>> = HashMap::new();
+        for row in self.fetch_all(&sql).await? {
+            policies
+                .entry((row.get(0), row.get(1)))
+                .or_default()
+                .push(Policy {
+                    name: row.get(2),
+                    command: row.get(3),
+                    permissive: row.get(4),
+                    roles: row.get(5),
+                    using: row.get(6),
+                    with_check: row.get(7),
+                });
+        }
+        Ok(policies)
+    }
+
+    /// 执行查询并返回全部行，事务级连接池下使用简单查询协议，不依赖预编译语句
+    async fn fetch_all(&self, sql: &str) -> Result<Vec<PgRow>, MetaError> {
+        if self.transaction_pooling {
+            self.raw_fetch_all(sql).await
+        } else {
+            Ok(sqlx::query(sql).fetch_all(&self.pool).await?)
+        }
+    }
+
+    /// 执行查询并返回第一行
+    async fn fetch_one(&self, sql: &str) -> Result<PgRow, MetaError> {
+        let rows = self.fetch_all(sql).await?;
+        rows.into_iter()
+            .next()
+            .ok_or_else(|| MetaError::from(sqlx::Error::RowNotFound))
+    }
+
+    /// 以简单查询协议执行并返回全部行；有会话设置时在同一事务内先执行 SET LOCAL，
+    /// 事务级连接池下启动参数不会传到实际执行语句的连接
+    async fn raw_fetch_all(&self, sql: &str) -> Result<Vec<PgRow>, MetaError> {
+        let Some(settings) = &self.local_settings else {
+            return Ok(sqlx::raw_sql(sql).fetch_all(&self.pool).await?);
+        };
+        // 不带参数的 &str 同样走简单查询协议
+        let mut tx = self.pool.begin().await?;
+        tx.execute(settings.as_str()).await?;
+        let rows = tx.fetch_all(sql).await?;
+        tx.commit().await?;
+        Ok(rows)
+    }
+
+    /// 采集范围的 schema 列表，用于 `IN (...)`
+    fn schema_list(&self) -> String {
+        quote_literal_list(&self.schemas, Dialect::Postgresql)
+    }
+}
+
+/// 把 pg_constraint 中的动作代码转换为 SQL 中的写法
+fn referential_action(column: &str) -> String {
+    format!(
+        "CASE {column} WHEN 'r' THEN 'RESTRICT' WHEN 'c' THEN 'CASCADE' WHEN 'n' THEN 'SET NULL' \
+         WHEN 'd' THEN 'SET DEFAULT' ELSE 'NO ACTION' END"
+    )
+}
+
+/// 连接时作为启动参数（`-c name=value`）传入的会话设置，对连接上的每个会话生效。
+/// 值中的空格和反斜杠需转义，否则会被拆成多个参数。
+/// 事务级连接池（pgbouncer）会拒绝未知的启动参数，此时不传，改由 [`local_settings`] 在每个事务内设置
+pub(crate) fn startup_options(conn_config: &ConnConfig) -> Vec<(String, String)> {
+    let mut settings = Vec::new();
+    if conn_config.transaction_pooling {
+        return settings;
     }
+    let timeouts = [
+        ("statement_timeout", conn_config.statement_timeout),
+        ("lock_timeout", conn_config.lock_timeout),
+    ];
+    for (name, timeout) in timeouts {
+        if let Some(timeout) = timeout {
+            settings.push((name.to_string(), timeout.as_millis().to_string()));
+        }
+    }
+    if conn_config.read_only {
+        // 会话内的事务默认只读，写语句由数据库拒绝
+        settings.push(("default_transaction_read_only".to_string(), "on".to_string()));
+    }
+    let mut custom: Vec<_> = conn_config.connect_options.iter().collect();
+    custom.sort();
+    for (name, value) in custom {
+        settings.push((name.clone(), value.replace('\\', "\\\\").replace(' ', "\\ ")));
+    }
+    settings
+}
+
+/// 事务级连接池下每个事务开头执行的设置语句，与 [`startup_options`] 的设置相同，只在当前事务内生效；
+/// 非事务级连接池或没有设置时为 None
+pub(crate) fn local_settings(conn_config: &ConnConfig) -> Option<String> {
+    if !conn_config.transaction_pooling {
+        return None;
+    }
+    let mut statements = Vec::new();
+    if conn_config.read_only {
+        statements.push("SET TRANSACTION READ ONLY".to_string());
+    }
+    let timeouts = [
+        ("statement_timeout", conn_config.statement_timeout),
+        ("lock_timeout", conn_config.lock_timeout),
+    ];
+    for (name, timeout) in timeouts {
+        if let Some(timeout) = timeout {
+            statements.push(format!("SET LOCAL {name} = {}", timeout.as_millis()));
+        }
+    }
+    // 参数名已在 ConnConfig::validate 中校验
+    let mut custom: Vec<_> = conn_config.connect_options.iter().collect();
+    custom.sort();
+    for (name, value) in custom {
+        statements.push(format!(
+            "SET LOCAL {name} = {}",
+            quote_literal(value, Dialect::Postgresql)
+        ));
+    }
+    (!statements.is_empty()).then(|| statements.join("; "))
+}
+
+/// 采集范围内的 schema：配置中逗号分隔的 schema，未配置时为 public
+pub(crate) fn configured_schemas(schema: Option<&str>) -> Vec<String> {
+    let schemas: Vec<String> = schema
+        .into_iter()
+        .flat_map(|schema| schema.split(','))
+        .map(|schema| schema.trim().to_string())
+        .filter(|schema| !schema.is_empty())
+        .collect();
+    if schemas.is_empty() {
+        vec!["public".to_string()]
+    } else {
+        schemas
+    }
+}
+
+/// 解析选项数组（`ftoptions`、`srvoptions`），元素形如 `key=value`
+fn parse_options(options: Option<Vec<String>>) -> BTreeMap<String, String> {
+    options
+        .unwrap_or_default()
+        .into_iter()
+        .map(|option| match option.split_once('=') {
+            Some((key, value)) => (key.to_string(), value.to_string()),
+            None => (option, String::new()),
+        })
+        .collect()
+}
+
+/// 注释的 SQL 表示，空注释清除原有注释
+fn comment_literal(comment: &str) -> String {
+    if comment.is_empty() {
+        "NULL".to_string()
+    } else {
+        quote_literal(comment, Dialect::Postgresql)
+    }
+}
+
+/// 修改表注释的语句
+pub(crate) fn table_comment_sql(table: &QualifiedName, comment: &str) -> String {
+    format!(
+        "COMMENT ON TABLE {} IS {}",
+        table.render_with_default(Dialect::Postgresql, None),
+        comment_literal(comment)
+    )
+}
+
+/// 修改字段注释的语句
+pub(crate) fn column_comment_sql(table: &QualifiedName, column: &str, comment: &str) -> String {
+    format!(
+        "COMMENT ON COLUMN {}.{} IS {}",
+        table.render_with_default(Dialect::Postgresql, None),
+        quote_ident(column, Dialect::Postgresql),
+        comment_literal(comment)
+    )
 }
 
 /// MetaTrait trait的异步实现
@@ -43,17 +277,26 @@ impl PgMeta {
 impl MetaTrait for PgMeta {
     /// 获取所有表信息
     async fn get_tables(&self) -> Result<Vec<TableInfo>, MetaError> {
-        let sql = r"SELECT
+        let sql = format!(
+            r"SELECT
        n.nspname AS TABLE_SCHEM,
        c.relname AS TABLE_NAME,
-       d.description AS REMARKS
+       d.description AS REMARKS,
+       pg_catalog.pg_get_userbyid(c.relowner)::text AS OWNER,
+       c.relrowsecurity AS ROW_SECURITY,
+       to_char(GREATEST(s.last_vacuum, s.last_autovacuum, s.last_analyze, s.last_autoanalyze),
+               'YYYY-MM-DD HH24:MI:SS') AS UPDATED_AT
 FROM pg_catalog.pg_namespace n,
      pg_catalog.pg_class c
          LEFT JOIN pg_catalog.pg_description d
                    ON (c.oid = d.objoid AND d.objsubid = 0 and d.classoid = 'pg_class'::regclass)
-WHERE c.relnamespace = n.oid and n.nspname = 'public' and c.relkind = 'r';";
+         LEFT JOIN pg_catalog.pg_stat_user_tables s ON s.relid = c.oid
+WHERE c.relnamespace = n.oid and n.nspname IN ({}) and c.relkind = 'r';",
+            self.schema_list()
+        );
 
-        let result = sqlx::query(sql).fetch_all(&self.pool).await?;
+        let result = self.fetch_all(&sql).await?;
+        let mut policies = self.get_policies().await?;
 
         let tables = result
             .iter()
@@ -61,7 +304,16 @@ WHERE c.relnamespace = n.oid and n.nspname = 'public' and c.relkind = 'r';";
                 let schema: String = row.get(0);
                 let table_name: String = row.get(1);
                 let comment: Option<String> = row.get(2);
-                TableInfo::new(schema, table_name, comment)
+                let policies = policies
+                    .remove(&(schema.clone(), table_name.clone()))
+                    .unwrap_or_default();
+                TableInfo {
+                    owner: row.get(3),
+                    row_security: row.get(4),
+                    updated_at: row.get(5),
+                    policies,
+                    ..TableInfo::new(schema, table_name, comment)
+                }
             })
             .collect();
 
@@ -70,7 +322,7 @@ WHERE c.relnamespace = n.oid and n.nspname = 'public' and c.relkind = 'r';";
 
     /// 设置表的主键信息
     async fn set_primary_key(&self, table_vec: &mut Vec<TableInfo>) -> Result<(), MetaError> {
-        let sql = "SELECT result.TABLE_SCHEMA, result.TABLE_NAME, result.COLUMN_NAME, result.KEY_SEQ, result.PK_NAME
+        let sql = format!("SELECT result.TABLE_SCHEMA, result.TABLE_NAME, result.COLUMN_NAME, result.KEY_SEQ, result.PK_NAME
 FROM (
     SELECT
         n.nspname AS TABLE_SCHEMA,
@@ -85,19 +337,19 @@ FROM (
     JOIN pg_catalog.pg_index i ON (a.attrelid = i.indrelid AND i.indisprimary = true)
     JOIN pg_catalog.pg_class ci ON (ci.oid = i.indexrelid)
     WHERE a.attnum = ANY(i.indkey)
-        AND n.nspname = 'public'
+        AND n.nspname IN ({})
 ) result
-ORDER BY result.table_name, result.pk_name, result.key_seq";
+ORDER BY result.table_name, result.pk_name, result.key_seq", self.schema_list());
 
-        let result = sqlx::query(sql).fetch_all(&self.pool).await?;
+        let result = self.fetch_all(&sql).await?;
 
-        let pk_map: HashMap<String, (String, String)> = result
+        let pk_map: HashMap<(String, String), (String, String)> = result
             .into_iter()
-            .map(|row| (row.get(1), (row.get(2), row.get(4))))
+            .map(|row| ((row.get(0), row.get(1)), (row.get(2), row.get(4))))
             .collect();
 
         for table in table_vec {
-            if let Some(pk) = pk_map.get(&table.table_name) {
+            if let Some(pk) = pk_map.get(&(table.schema.clone(), table.table_name.clone())) {
                 table.set_pk_name(pk.clone().1);
                 table.set_pk_column(pk.clone().0);
             }
@@ -108,7 +360,7 @@ ORDER BY result.table_name, result.pk_name, result.key_seq";
 
     /// 设置表的索引信息
     async fn set_index_key(&self, table_vec: &mut Vec<TableInfo>) -> Result<(), MetaError> {
-        let sql = "
+        let sql = format!("
 SELECT
     n.nspname AS schema_name,
     ct.relname AS table_name,
@@ -129,39 +381,42 @@ JOIN
 JOIN
     UNNEST(i.indkey) WITH ORDINALITY AS ia(attnum, ord) ON a.attnum = ia.attnum
 WHERE
-    n.nspname = 'public'
+    n.nspname IN ({})
 GROUP BY
     n.nspname, ct.relname, ci.relname, i.indisunique, i.indexrelid
 ORDER BY
-    n.nspname, ct.relname, ci.relname;";
+    n.nspname, ct.relname, ci.relname;", self.schema_list());
 
-        let result = sqlx::query(sql).fetch_all(&self.pool).await?;
+        let result = self.fetch_all(&sql).await?;
 
-        let mut index_map: HashMap<String, Vec<IndexInfo>> = HashMap::new();
+        let mut index_map: HashMap<(String, String), Vec<IndexInfo>> = HashMap::new();
         for row in result {
             let is_unique = row.get::<String, usize>(3) == "UNIQUE";
             let indexed_column_names = row.get::<String, usize>(5);
             let columns = indexed_column_names.split(",").collect::<Vec<&str>>();
 
+            let schema = row.get::<String, usize>(0);
             let table_name = row.get::<String, usize>(1);
             let index_name = row.get::<String, usize>(2);
             let index_def = row.get::<String, usize>(4);
             
             columns.iter().for_each(|column| {
                 index_map
-                .entry(table_name.clone())
+                .entry((schema.clone(), table_name.clone()))
                 .or_insert_with(Vec::new)
                 .push(IndexInfo {
                     column_name: column.to_string(),
                     index_name: index_name.to_string(),
                     index_def: index_def.to_string(),
                     is_unique: is_unique,
+                    index_type: None,
+                    sub_part: None,
                 });
             });
         }
 
         for table in table_vec {
-            if let Some(indexes) = index_map.get(&table.table_name) {
+            if let Some(indexes) = index_map.get(&(table.schema.clone(), table.table_name.clone())) {
                 table.set_index_columns(indexes.clone());
             }
         }
@@ -169,13 +424,60 @@ ORDER BY
         Ok(())
     }
 
+    /// 设置表的外键，被引用字段按 confkey 的顺序与本表字段对应
+    async fn set_foreign_keys(&self, table_vec: &mut Vec<TableInfo>) -> Result<(), MetaError> {
+        let sql = format!(
+            "SELECT n.nspname::text, c.relname::text, con.conname::text,
+       ARRAY(SELECT a.attname::text FROM UNNEST(con.conkey) WITH ORDINALITY AS k(attnum, ord)
+             JOIN pg_catalog.pg_attribute a ON a.attrelid = con.conrelid AND a.attnum = k.attnum ORDER BY k.ord),
+       rn.nspname::text, rc.relname::text,
+       ARRAY(SELECT a.attname::text FROM UNNEST(con.confkey) WITH ORDINALITY AS k(attnum, ord)
+             JOIN pg_catalog.pg_attribute a ON a.attrelid = con.confrelid AND a.attnum = k.attnum ORDER BY k.ord),
+       {on_update}, {on_delete}
+FROM pg_catalog.pg_constraint con
+JOIN pg_catalog.pg_class c ON c.oid = con.conrelid
+JOIN pg_catalog.pg_namespace n ON n.oid = c.relnamespace
+JOIN pg_catalog.pg_class rc ON rc.oid = con.confrelid
+JOIN pg_catalog.pg_namespace rn ON rn.oid = rc.relnamespace
+WHERE con.contype = 'f' AND n.nspname IN ({schemas})
+ORDER BY n.nspname, c.relname, con.conname",
+            on_update = referential_action("con.confupdtype"),
+            on_delete = referential_action("con.confdeltype"),
+            schemas = self.schema_list()
+        );
+
+        let mut fk_map: HashMap<(String, String), Vec<ForeignKey>> = HashMap::new();
+        for row in self.fetch_all(&sql).await? {
+            fk_map
+                .entry((row.get(0), row.get(1)))
+                .or_default()
+                .push(ForeignKey {
+                    name: row.get(2),
+                    columns: row.get(3),
+                    referenced_schema: row.get(4),
+                    referenced_table: row.get(5),
+                    referenced_columns: row.get(6),
+                    on_update: row.get(7),
+                    on_delete: row.get(8),
+                });
+        }
+
+        for table in table_vec {
+            if let Some(foreign_keys) = fk_map.remove(&(table.schema.clone(), table.table_name.clone())) {
+                table.set_foreign_keys(foreign_keys);
+            }
+        }
+
+        Ok(())
+    }
+
     /// 设置表的列信息
     async fn set_columns(&self, table_vec: &mut Vec<TableInfo>) -> Result<(), MetaError> {
         let tables: Vec<_> = table_vec
             .iter()
             .map(|table| table.table_name.clone())
             .collect();
-        let tables_str = tables.join("','");
+        let tables_str = quote_literal_list(&tables, Dialect::Postgresql);
 
         let sql = format!(
             "select
@@ -191,52 +493,64 @@ ORDER BY
     col.column_default
 from
     information_schema.columns col left join pg_description des on
-        col.table_name::regclass = des.objoid
+        (quote_ident(col.table_schema) || '.' || quote_ident(col.table_name))::regclass = des.objoid
             and col.ordinal_position = des.objsubid
 where
-    table_schema = 'public' and col.table_name in ('{}')",
+    table_schema IN ({}) and col.table_name in ({})
+order by
+    col.table_schema, col.table_name, col.ordinal_position",
+            self.schema_list(),
             tables_str
         );
 
-        let result = sqlx::query(&sql).fetch_all(&self.pool).await?;
+        let result = self.fetch_all(&sql).await?;
 
         let mut column_map = HashMap::new();
         let pk_map: HashMap<_, _> = table_vec
             .into_iter()
-            .map(|table| (table.table_name.clone(), table.pk_column.clone()))
+            .map(|table| {
+                (
+                    (table.schema.clone(), table.table_name.clone()),
+                    table.pk_column.clone(),
+                )
+            })
             .collect();
 
         for row in result {
             let is_nullable = row.get::<String, usize>(7) != "NO";
-            let table_name = row.get::<String, usize>(1);
+            let key = (row.get::<String, usize>(0), row.get::<String, usize>(1));
             let column_name = row.get::<String, usize>(2);
-            let is_pk = pk_map.get(&table_name) == Some(&column_name);
+            let is_pk = pk_map.get(&key) == Some(&column_name);
             let column_def = row.get::<Option<String>, usize>(9);
             let auto_increment = column_def
                 .clone()
                 .map(|def| is_pk && def.to_lowercase().starts_with("nextval"));
+            let length = row.get::<i32, usize>(4);
+            let digit = row.get::<Option<i32>, usize>(5);
 
             let column = Column {
                 name: column_name,
-                column_type: FieldTypeEnum::pg_field_type(row.get(3)),
+                column_type: FieldTypeEnum::pg_field_type(row.get(3)).with_precision(length, digit),
                 type_name: row.get(3),
-                length: row.get::<i32, usize>(4),
-                digit: row.get(5),
+                length,
+                digit,
                 comment: row.get(6),
                 auto_increment,
                 column_def,
                 is_nullable,
                 is_pk,
+                ordinal_position: u32::try_from(row.get::<i32, usize>(8)).unwrap_or_default(),
+                tags: Default::default(),
             };
 
             column_map
-                .entry(table_name)
+                .entry(key)
                 .or_insert_with(Vec::new)
                 .push(column);
         }
 
         for table in table_vec {
-            if let Some(columns) = column_map.get(&table.table_name) {
+            if let Some(columns) = column_map.get(&(table.schema.clone(), table.table_name.clone())) {
                 table.set_columns(columns.clone());
             }
         }
@@ -246,24 +560,31 @@ where
 
     /// 获取所有视图信息
     async fn get_views(&self) -> Result<Vec<ViewsInfo>, MetaError> {
-        let sql = r"SELECT
+        let sql = format!(
+            r"SELECT
        n.nspname AS TABLE_SCHEM,
        c.relname AS TABLE_NAME,
-       d.description AS REMARKS
+       d.description AS REMARKS,
+       pg_catalog.pg_get_viewdef(c.oid) AS DEFINITION
 FROM pg_catalog.pg_namespace n,
      pg_catalog.pg_class c
          LEFT JOIN pg_catalog.pg_description d
                    ON (c.oid = d.objoid AND d.objsubid = 0 and d.classoid = 'pg_class'::regclass)
-WHERE c.relnamespace = n.oid and n.nspname = 'public' and c.relkind = 'v';";
+WHERE c.relnamespace = n.oid and n.nspname IN ({}) and c.relkind = 'v';",
+            self.schema_list()
+        );
 
-        let result = sqlx::query(sql).fetch_all(&self.pool).await?;
+        let result = self.fetch_all(&sql).await?;
 
         let views = result
             .iter()
             .map(|row| {
                 let schema: String = row.get(0);
                 let view_name: String = row.get(1);
-                ViewsInfo::new(schema, view_name)
+                ViewsInfo {
+                    definition: row.get(3),
+                    ..ViewsInfo::new(schema, view_name)
+                }
             })
             .collect();
 
@@ -273,7 +594,7 @@ WHERE c.relnamespace = n.oid and n.nspname = 'public' and c.relkind = 'v';";
     /// 设置视图的列信息
     async fn set_view_columns(&self, view_vec: &mut Vec<ViewsInfo>) -> Result<(), MetaError> {
         let views: Vec<_> = view_vec.iter().map(|view| view.view_name.clone()).collect();
-        let views_str = views.join("','");
+        let views_str = quote_literal_list(&views, Dialect::Postgresql);
 
         let sql = format!(
             "select
@@ -289,43 +610,50 @@ WHERE c.relnamespace = n.oid and n.nspname = 'public' and c.relkind = 'v';";
     col.column_default
 from
     information_schema.columns col left join pg_description des on
-        col.table_name::regclass = des.objoid
+        (quote_ident(col.table_schema) || '.' || quote_ident(col.table_name))::regclass = des.objoid
             and col.ordinal_position = des.objsubid
 where
-    table_schema = 'public' and col.table_name in ('{}')",
+    table_schema IN ({}) and col.table_name in ({})
+order by
+    col.table_schema, col.table_name, col.ordinal_position",
+            self.schema_list(),
             views_str
         );
 
-        let result = sqlx::query(&sql).fetch_all(&self.pool).await?;
+        let result = self.fetch_all(&sql).await?;
         let mut column_map = HashMap::new();
 
         for row in result {
             let is_nullable = row.get::<String, usize>(7) != "NO";
-            let view_name = row.get::<String, usize>(1);
+            let key = (row.get::<String, usize>(0), row.get::<String, usize>(1));
             let column_name = row.get::<String, usize>(2);
             let column_def = row.get::<Option<String>, usize>(9);
+            let length = row.get::<i32, usize>(4);
+            let digit = row.get::<Option<i32>, usize>(5);
 
             let column = Column {
                 name: column_name,
-                column_type: FieldTypeEnum::pg_field_type(row.get(3)),
+                column_type: FieldTypeEnum::pg_field_type(row.get(3)).with_precision(length, digit),
                 type_name: row.get(3),
-                length: row.get(4),
-                digit: row.get(5),
+                length,
+                digit,
                 comment: row.get(6),
                 auto_increment: None,
                 column_def,
                 is_nullable,
                 is_pk: false,
+                ordinal_position: u32::try_from(row.get::<i32, usize>(8)).unwrap_or_default(),
+                tags: Default::default(),
             };
 
             column_map
-                .entry(view_name)
+                .entry(key)
                 .or_insert_with(Vec::new)
                 .push(column);
         }
 
         for view in view_vec {
-            if let Some(columns) = column_map.get(&view.view_name) {
+            if let Some(columns) = column_map.get(&(view.schema.clone(), view.view_name.clone())) {
                 view.set_columns(columns.clone());
             }
         }
@@ -335,13 +663,13 @@ where
 
     /// 执行计数SQL查询
     async fn count(&self, sql: &str) -> Result<i64, MetaError> {
-        let result = sqlx::query(&sql).fetch_one(&self.pool).await?;
+        let result = self.fetch_one(sql).await?;
         Ok(result.get(0))
     }
 
     /// 执行查询并返回结果集
     async fn query(&self, sql: &str) -> Result<Vec<Vec<String>>, MetaError> {
-        let result = sqlx::query(&sql).fetch_all(&self.pool).await?;
+        let result = self.fetch_all(sql).await?;
 
         let rows = result
             .iter()
@@ -350,4 +678,270 @@ where
 
         Ok(rows)
     }
+
+    /// 字段查询按表名过滤，分批加载以便报告进度并限制 IN 列表长度
+    fn column_batch_size(&self) -> Option<usize> {
+        Some(COLUMN_BATCH_SIZE)
+    }
+
+    /// 以表及其字段、默认值、索引、注释在系统表中的行版本（xmin）作为变更标记，
+    /// 任何 DDL 或 COMMENT 都会改变标记。pg_stat_user_tables 的计数只反映数据变化，不用于判断结构变更
+    async fn change_markers(&self) -> Result<Option<HashMap<(String, String), String>>, MetaError> {
+        let sql = format!(
+            "SELECT n.nspname::text, c.relname::text,
+       concat_ws('/', c.xmin,
+           (SELECT md5(string_agg(a.xmin::text, ',' ORDER BY a.attnum)) FROM pg_catalog.pg_attribute a WHERE a.attrelid = c.oid),
+           (SELECT md5(string_agg(ad.xmin::text, ',' ORDER BY ad.adnum)) FROM pg_catalog.pg_attrdef ad WHERE ad.adrelid = c.oid),
+           (SELECT md5(string_agg(i.xmin::text, ',' ORDER BY i.indexrelid)) FROM pg_catalog.pg_index i WHERE i.indrelid = c.oid),
+           (SELECT md5(string_agg(con.xmin::text, ',' ORDER BY con.oid)) FROM pg_catalog.pg_constraint con
+             WHERE con.conrelid = c.oid AND con.contype = 'f'),
+           (SELECT md5(string_agg(d.xmin::text, ',' ORDER BY d.objsubid)) FROM pg_catalog.pg_description d
+             WHERE d.objoid = c.oid AND d.classoid = 'pg_catalog.pg_class'::regclass))
+FROM pg_catalog.pg_class c
+JOIN pg_catalog.pg_namespace n ON c.relnamespace = n.oid
+WHERE n.nspname IN ({}) AND c.relkind = 'r'",
+            self.schema_list()
+        );
+        let rows = self.fetch_all(&sql).await?;
+        let markers = rows
+            .iter()
+            .map(|row| ((row.get(0), row.get(1)), row.get(2)))
+            .collect();
+        Ok(Some(markers))
+    }
+
+    /// 列出数据库，模板库视为系统库
+    async fn list_databases(&self, include_system: bool) -> Result<Vec<String>, MetaError> {
+        let filter = if include_system {
+            ""
+        } else {
+            "WHERE NOT datistemplate"
+        };
+        let sql = format!("SELECT datname::text FROM pg_database {filter} ORDER BY datname");
+        let rows = self.fetch_all(&sql).await?;
+        Ok(rows.iter().map(|row| row.get(0)).collect())
+    }
+
+    /// 列出 schema，pg_catalog、pg_toast 等 pg_ 开头的和 information_schema 视为系统 schema
+    async fn list_schemas(&self, include_system: bool) -> Result<Vec<String>, MetaError> {
+        let filter = if include_system {
+            ""
+        } else {
+            r"WHERE schema_name NOT LIKE 'pg\_%' AND schema_name <> 'information_schema'"
+        };
+        let sql = format!(
+            "SELECT schema_name::text FROM information_schema.schemata {filter} ORDER BY schema_name"
+        );
+        let rows = self.fetch_all(&sql).await?;
+        Ok(rows.iter().map(|row| row.get(0)).collect())
+    }
+
+    /// 直接展开系统表中的 ACL，而不是查询 information_schema.table_privileges，
+    /// 后者只列出与当前用户相关的授权。未显式授权的表按默认 ACL（所有者拥有全部权限）展开
+    async fn get_privileges(&self) -> Result<Vec<Privilege>, MetaError> {
+        let sql = format!(
+            "SELECT n.nspname::text, c.relname::text, NULL::text,
+       CASE WHEN x.grantee = 0 THEN 'PUBLIC' ELSE pg_catalog.pg_get_userbyid(x.grantee)::text END,
+       x.privilege_type::text, x.is_grantable
+FROM pg_catalog.pg_class c
+JOIN pg_catalog.pg_namespace n ON c.relnamespace = n.oid,
+     aclexplode(coalesce(c.relacl, acldefault('r', c.relowner))) x
+WHERE n.nspname IN ({schemas}) AND c.relkind IN ('r', 'p', 'v', 'm', 'f')
+UNION ALL
+SELECT n.nspname::text, c.relname::text, a.attname::text,
+       CASE WHEN x.grantee = 0 THEN 'PUBLIC' ELSE pg_catalog.pg_get_userbyid(x.grantee)::text END,
+       x.privilege_type::text, x.is_grantable
+FROM pg_catalog.pg_class c
+JOIN pg_catalog.pg_namespace n ON c.relnamespace = n.oid
+JOIN pg_catalog.pg_attribute a ON a.attrelid = c.oid AND a.attnum > 0 AND NOT a.attisdropped,
+     aclexplode(a.attacl) x
+WHERE n.nspname IN ({schemas}) AND c.relkind IN ('r', 'p', 'v', 'm', 'f')
+ORDER BY 1, 2, 3 NULLS FIRST, 4, 5",
+            schemas = self.schema_list()
+        );
+        let rows = self.fetch_all(&sql).await?;
+        Ok(rows
+            .iter()
+            .map(|row| Privilege {
+                schema: row.get(0),
+                table: row.get(1),
+                column: row.get(2),
+                grantee: row.get(3),
+                privilege: row.get(4),
+                grantable: row.get(5),
+            })
+            .collect())
+    }
+
+    /// 外部表（relkind = 'f'）及其服务器和选项，字段与普通表一样从 information_schema.columns 读取
+    async fn get_foreign_tables(&self) -> Result<Vec<ForeignTable>, MetaError> {
+        let sql = format!(
+            r"SELECT n.nspname::text, c.relname::text, d.description, s.srvname::text, ft.ftoptions::text[]
+FROM pg_catalog.pg_foreign_table ft
+JOIN pg_catalog.pg_class c ON c.oid = ft.ftrelid
+JOIN pg_catalog.pg_namespace n ON n.oid = c.relnamespace
+JOIN pg_catalog.pg_foreign_server s ON s.oid = ft.ftserver
+LEFT JOIN pg_catalog.pg_description d
+          ON (c.oid = d.objoid AND d.objsubid = 0 AND d.classoid = 'pg_class'::regclass)
+WHERE n.nspname IN ({})",
+            self.schema_list()
+        );
+        let rows = self.fetch_all(&sql).await?;
+        if rows.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let mut tables: Vec<TableInfo> = rows
+            .iter()
+            .map(|row| TableInfo::new(row.get(0), row.get(1), None))
+            .collect();
+        self.set_columns(&mut tables).await?;
+        Ok(rows
+            .iter()
+            .zip(tables)
+            .map(|(row, table)| ForeignTable {
+                schema: table.schema,
+                table_name: table.table_name,
+                comment: row.get(2),
+                server: row.get(3),
+                options: parse_options(row.get(4)),
+                columns: table.columns,
+            })
+            .collect())
+    }
+
+    /// 外部服务器；用户映射只取角色名，映射选项中有远端口令
+    async fn get_foreign_servers(&self) -> Result<Vec<ForeignServer>, MetaError> {
+        let sql = "SELECT s.srvname::text, w.fdwname::text, s.srvtype, s.srvversion, s.srvoptions::text[],
+       ARRAY(SELECT um.usename::text FROM pg_catalog.pg_user_mappings um
+             WHERE um.srvid = s.oid ORDER BY 1)
+FROM pg_catalog.pg_foreign_server s
+JOIN pg_catalog.pg_foreign_data_wrapper w ON w.oid = s.srvfdw
+ORDER BY s.srvname";
+        let rows = self.fetch_all(sql).await?;
+        Ok(rows
+            .iter()
+            .map(|row| ForeignServer {
+                name: row.get(0),
+                wrapper: row.get(1),
+                server_type: row.get(2),
+                version: row.get(3),
+                options: parse_options(row.get(4)),
+                user_mappings: row.get(5),
+            })
+            .collect())
+    }
+
+    /// 执行不返回结果的语句，事务级连接池下使用简单查询协议
+    async fn execute(&self, sql: &str) -> Result<(), MetaError> {
+        if self.transaction_pooling {
+            self.raw_fetch_all(sql).await?;
+        } else {
+            sqlx::query(sql).execute(&self.pool).await?;
+        }
+        Ok(())
+    }
+
+    /// 修改表注释
+    async fn set_table_comment(
+        &self,
+        table: &QualifiedName,
+        comment: &str,
+    ) -> Result<(), MetaError> {
+        self.execute(&table_comment_sql(table, comment)).await
+    }
+
+    /// 修改字段注释
+    async fn set_column_comment(
+        &self,
+        table: &QualifiedName,
+        column: &str,
+        comment: &str,
+    ) -> Result<(), MetaError> {
+        self.execute(&column_comment_sql(table, column, comment)).await
+    }
+
+    /// 抽样表数据；随机抽样按统计行数估算 TABLESAMPLE 比例，表未分析过时退化为全表随机排序。
+    /// 使用简单查询协议以文本格式取值，再按字段类型转换
+    async fn sample(
+        &self,
+        table: &QualifiedName,
+        n: usize,
+        strategy: SampleStrategy,
+    ) -> Result<SampleRows, MetaError> {
+        let table = table.render_with_default(Dialect::Postgresql, None);
+        let sql = match strategy {
+            SampleStrategy::First => format!("SELECT * FROM {table} LIMIT {n}"),
+            SampleStrategy::Random => {
+                let sql = format!(
+                    "SELECT reltuples::float8 FROM pg_class WHERE oid = to_regclass({})",
+                    quote_literal(&table, Dialect::Postgresql)
+                );
+                let reltuples: Option<f64> = self.fetch_one(&sql).await?.get(0);
+                match reltuples {
+                    Some(reltuples) if reltuples > 0.0 => {
+                        // 多抽一倍，避免按比例抽样后不足 n 行
+                        let percent = (n as f64 * 200.0 / reltuples).clamp(0.0001, 100.0);
+                        format!(
+                            "SELECT * FROM {table} TABLESAMPLE BERNOULLI ({percent:.4}) ORDER BY random() LIMIT {n}"
+                        )
+                    }
+                    _ => format!("SELECT * FROM {table} ORDER BY random() LIMIT {n}"),
+                }
+            }
+        };
+
+        let rows = self.raw_fetch_all(&sql).await?;
+        to_sample_rows(&rows)
+    }
+
+    /// 使用简单查询协议流式读取，按批返回。事务级连接池下有会话设置时，
+    /// 需要在同一事务内执行，改为整体读取后再分批
+    fn query_stream<'a>(
+        &'a self,
+        sql: &'a str,
+        batch_size: usize,
+    ) -> BoxStream<'a, Result<SampleRows, MetaError>> {
+        if self.local_settings.is_some() {
+            return stream::once(self.raw_fetch_all(sql))
+                .map_ok(move |rows| {
+                    let batches: Vec<_> = rows
+                        .chunks(batch_size.max(1))
+                        .map(to_sample_rows)
+                        .collect();
+                    stream::iter(batches)
+                })
+                .try_flatten()
+                .boxed();
+        }
+        sqlx::raw_sql(sql)
+            .fetch(&self.pool)
+            .try_chunks(batch_size.max(1))
+            .map(|rows| to_sample_rows(&rows.map_err(|e| e.1)?))
+            .boxed()
+    }
+}
+
+/// 将文本格式的结果行按字段类型转换
+fn to_sample_rows(rows: &[PgRow]) -> Result<SampleRows, MetaError> {
+    let columns = rows
+        .first()
+        .map(|row| row.columns().iter().map(|c| c.name().to_string()).collect())
+        .unwrap_or_default();
+    let rows = rows
+        .iter()
+        .map(|row| {
+            row.columns()
+                .iter()
+                .map(|column| {
+                    let text: Option<&str> = row.try_get_unchecked(column.ordinal())?;
+                    Ok(text.map_or(Value::Null, |text| {
+                        Value::from_text(text, column.type_info().name())
+                    }))
+                })
+                .collect()
+        })
+        .collect::<Result<_, MetaError>>()?;
+
+    Ok(SampleRows { columns, rows })
 }
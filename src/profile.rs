@@ -0,0 +1,298 @@
+use std::collections::HashMap;
+
+use derive_builder::Builder;
+use serde::{Deserialize, Serialize};
+
+use crate::error::MetaError;
+use crate::ident::{Dialect, qualified_name, quote_ident, quote_literal};
+use crate::meta::MetadataService;
+use crate::modal::{Column, FieldTypeEnum, TableInfo};
+
+/// 数据画像选项
+#[derive(Debug, Clone, Builder)]
+#[builder(default)]
+pub struct ProfileOptions {
+    // 每个字段统计出现次数最多的前 k 个值，为 0 时不统计
+    pub top_k: usize,
+    // 优先使用统计信息估算去重数（目前仅 PostgreSQL 的 pg_stats），没有统计信息的字段仍精确计算
+    pub estimate_distinct: bool,
+}
+
+impl Default for ProfileOptions {
+    fn default() -> Self {
+        Self {
+            top_k: 5,
+            estimate_distinct: false,
+        }
+    }
+}
+
+/// 单个字段的数据画像
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ColumnProfile {
+    // 字段名
+    pub column: String,
+    // 表的总行数
+    pub row_count: u64,
+    // 空值行数
+    pub null_count: u64,
+    // 空值占比，空表为 0
+    pub null_ratio: f64,
+    // 去重后的值个数
+    pub distinct_count: u64,
+    // 去重数是否来自统计信息的估算
+    pub distinct_estimated: bool,
+    // 最小值，不可比较的类型（json、二进制、布尔等）或全为空时为 None
+    pub min: Option<String>,
+    // 最大值
+    pub max: Option<String>,
+    // 出现次数最多的值及其次数，按次数降序
+    pub top_values: Vec<(String, u64)>,
+    // 平均长度，文本按字符数、二进制按字节数，其它类型为 None
+    pub avg_length: Option<f64>,
+}
+
+impl MetadataService {
+    /// 对已采集到的表做字段级数据画像：一条聚合 SQL 统计所有字段的空值、去重数、最值和平均长度，
+    /// 再对每个字段执行一次分组查询得到高频值。大表上会全表扫描，调用方应控制调用时机
+    pub async fn profile(
+        &self,
+        table: &TableInfo,
+        options: &ProfileOptions,
+    ) -> Result<Vec<ColumnProfile>, MetaError> {
+        let dialect = self.dialect();
+        let handler = self.create_metadata_handler().await?;
+        let from = qualified_name(&table.schema, &table.table_name, dialect);
+
+        let estimates = if options.estimate_distinct && dialect == Dialect::Postgresql {
+            let sql = format!(
+                "SELECT attname::text, n_distinct::text FROM pg_stats WHERE schemaname = {} AND tablename = {}",
+                quote_literal(&table.schema, dialect),
+                quote_literal(&table.table_name, dialect)
+            );
+            handler
+                .query(&sql)
+                .await?
+                .into_iter()
+                .filter_map(|row| Some((row.first()?.clone(), row.get(1)?.parse::<f64>().ok()?)))
+                .collect()
+        } else {
+            HashMap::new()
+        };
+
+        let plans: Vec<Plan> = table
+            .columns
+            .iter()
+            .map(|column| Plan::new(column, dialect, estimates.get(&column.name).copied()))
+            .collect();
+        let mut select = vec![text(dialect, "COUNT(*)")];
+        select.extend(plans.iter().flat_map(|plan| plan.expressions.clone()));
+        let sql = format!("SELECT {} FROM {from}", select.join(", "));
+        let row = handler
+            .query(&sql)
+            .await?
+            .into_iter()
+            .next()
+            .unwrap_or_default();
+
+        let mut values = row.into_iter();
+        let row_count = parse_count(values.next())?;
+        let mut profiles = Vec::with_capacity(plans.len());
+        for plan in &plans {
+            let mut profile = plan.read(row_count, &mut values)?;
+            if options.top_k > 0 && plan.top_values {
+                let value = text(dialect, &plan.ident);
+                let sql = format!(
+                    "SELECT {value} AS v, {count} AS c FROM {from} WHERE {ident} IS NOT NULL GROUP BY v ORDER BY COUNT(*) DESC, v LIMIT {k}",
+                    count = text(dialect, "COUNT(*)"),
+                    ident = plan.ident,
+                    k = options.top_k
+                );
+                profile.top_values = handler
+                    .query(&sql)
+                    .await?
+                    .into_iter()
+                    .map(|row| {
+                        let mut row = row.into_iter();
+                        Ok((row.next().unwrap_or_default(), parse_count(row.next())?))
+                    })
+                    .collect::<Result<_, MetaError>>()?;
+            }
+            profiles.push(profile);
+        }
+        Ok(profiles)
+    }
+}
+
+/// 可以比较大小的文本类型名；无法识别的类型（如 point、xml、tsvector、geometry）同样映射为
+/// `FieldTypeEnum::String`，但不一定有比较运算符，不能求最值，去重时转为文本
+const TEXT_TYPES: &[&str] = &[
+    "char",
+    "character",
+    "varchar",
+    "character varying",
+    "bpchar",
+    "text",
+    "name",
+    "citext",
+    "tinytext",
+    "mediumtext",
+    "longtext",
+    "enum",
+    "set",
+    "string",
+    "fixedstring",
+];
+
+/// 类型名是否在 [`TEXT_TYPES`] 中，忽略长度参数及 ClickHouse 的 Nullable、LowCardinality 包装
+fn is_text_type(type_name: &str) -> bool {
+    let mut type_name = type_name.trim().to_lowercase();
+    for wrapper in ["nullable(", "lowcardinality("] {
+        if let Some(inner) = type_name.strip_prefix(wrapper) {
+            type_name = inner.trim_end_matches(')').to_string();
+        }
+    }
+    let name = type_name.split('(').next().unwrap_or_default().trim();
+    TEXT_TYPES.contains(&name)
+}
+
+/// 单个字段在聚合 SQL 中的统计项
+struct Plan {
+    // 字段名
+    column: String,
+    // 引用后的字段名
+    ident: String,
+    // 按顺序追加到 SELECT 列表的表达式
+    expressions: Vec<String>,
+    // 统计信息中的 n_distinct，负数表示占总行数的比例
+    estimate: Option<f64>,
+    // 是否统计最值
+    min_max: bool,
+    // 是否统计平均长度
+    avg_length: bool,
+    // 是否统计高频值
+    top_values: bool,
+}
+
+impl Plan {
+    fn new(column: &Column, dialect: Dialect, estimate: Option<f64>) -> Self {
+        let ident = quote_ident(&column.name, dialect);
+        let textual = match column.column_type {
+            FieldTypeEnum::String | FieldTypeEnum::Character => is_text_type(&column.type_name),
+            FieldTypeEnum::Clob | FieldTypeEnum::Json => true,
+            _ => false,
+        };
+        // 文本按类型名判断，自定义类型无法确定是否可比较
+        let comparable = match column.column_type {
+            FieldTypeEnum::String | FieldTypeEnum::Character => textual,
+            FieldTypeEnum::Json
+            | FieldTypeEnum::Blob
+            | FieldTypeEnum::Clob
+            | FieldTypeEnum::ByteArray
+            | FieldTypeEnum::Array(_)
+            | FieldTypeEnum::Object
+            | FieldTypeEnum::Boolean
+            | FieldTypeEnum::Uuid
+            | FieldTypeEnum::Custom(_) => false,
+            _ => true,
+        };
+        let binary = matches!(
+            column.column_type,
+            FieldTypeEnum::Blob | FieldTypeEnum::ByteArray
+        );
+
+        let mut expressions = vec![text(dialect, &format!("COUNT({ident})"))];
+        if estimate.is_none() {
+            // 不可比较的类型在 PostgreSQL 中可能没有相等运算符，按文本去重
+            let distinct = if comparable {
+                ident.clone()
+            } else {
+                text(dialect, &ident)
+            };
+            expressions.push(text(dialect, &format!("COUNT(DISTINCT {distinct})")));
+        }
+        if comparable {
+            expressions.push(nullable_text(dialect, &format!("MIN({ident})")));
+            expressions.push(nullable_text(dialect, &format!("MAX({ident})")));
+        }
+        if textual || binary {
+            let length = match (dialect, binary) {
+                (_, true) => format!("OCTET_LENGTH({ident})"),
+                (Dialect::MySql, false) => format!("CHAR_LENGTH({ident})"),
+                (_, false) => format!("LENGTH({})", text(dialect, &ident)),
+            };
+            expressions.push(nullable_text(dialect, &format!("AVG({length})")));
+        }
+
+        Self {
+            column: column.name.clone(),
+            ident,
+            expressions,
+            estimate,
+            min_max: comparable,
+            avg_length: textual || binary,
+            top_values: !binary,
+        }
+    }
+
+    /// 按 `expressions` 的顺序读取聚合结果
+    fn read(
+        &self,
+        row_count: u64,
+        values: &mut impl Iterator<Item = String>,
+    ) -> Result<ColumnProfile, MetaError> {
+        let non_null = parse_count(values.next())?;
+        let (distinct_count, distinct_estimated) = match self.estimate {
+            Some(n) if n < 0.0 => ((-n * row_count as f64).round() as u64, true),
+            Some(n) => (n.round() as u64, true),
+            None => (parse_count(values.next())?, false),
+        };
+        // 全为空时聚合结果为空值，查询结果中表现为空串
+        let mut aggregate = |enabled: bool| {
+            let value = if enabled { values.next() } else { None };
+            value.filter(|_| non_null > 0)
+        };
+        let min = aggregate(self.min_max);
+        let max = aggregate(self.min_max);
+        let avg_length = aggregate(self.avg_length).and_then(|v| v.parse().ok());
+
+        let null_count = row_count.saturating_sub(non_null);
+        Ok(ColumnProfile {
+            column: self.column.clone(),
+            row_count,
+            null_count,
+            null_ratio: if row_count == 0 {
+                0.0
+            } else {
+                null_count as f64 / row_count as f64
+            },
+            distinct_count,
+            distinct_estimated,
+            min,
+            max,
+            top_values: vec![],
+            avg_length,
+        })
+    }
+}
+
+/// 将表达式转换为文本，通用的查询接口只能按字符串读取结果
+pub(crate) fn text(dialect: Dialect, expr: &str) -> String {
+    match dialect {
+        Dialect::MySql => format!("CAST({expr} AS CHAR)"),
+        Dialect::Postgresql | Dialect::Sqlite => format!("CAST({expr} AS TEXT)"),
+    }
+}
+
+/// 可能为空值的聚合结果转换为文本，空值以空串表示
+fn nullable_text(dialect: Dialect, expr: &str) -> String {
+    format!("COALESCE({}, '')", text(dialect, expr))
+}
+
+/// 解析计数结果
+fn parse_count(value: Option<String>) -> Result<u64, MetaError> {
+    let value = value.unwrap_or_default();
+    value
+        .parse()
+        .map_err(|_| MetaError::DbException(format!("无法解析计数结果: {value}")))
+}
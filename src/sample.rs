@@ -0,0 +1,75 @@
+use serde::{Deserialize, Serialize};
+
+/// 抽样方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum SampleStrategy {
+    /// 按存储顺序取前 n 行，开销最小
+    #[default]
+    First,
+    /// 随机抽取 n 行
+    Random,
+}
+
+/// 抽样得到的单个值，按数据库字段类型转换
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    Text(String),
+    Bytes(Vec<u8>),
+}
+
+#[cfg_attr(not(any(feature = "mysql", feature = "postgres")), allow(dead_code))]
+impl Value {
+    /// 将文本协议返回的值按字段类型名转换，无法识别或超出范围时保留为文本
+    pub(crate) fn from_text(text: &str, type_name: &str) -> Self {
+        let type_name = type_name.to_uppercase();
+        let base = type_name.split_whitespace().next().unwrap_or_default();
+        match base {
+            "BOOL" | "BOOLEAN" => match text {
+                "t" | "true" | "1" => Value::Bool(true),
+                "f" | "false" | "0" => Value::Bool(false),
+                _ => Value::Text(text.to_string()),
+            },
+            "INT2" | "INT4" | "INT8" | "TINYINT" | "SMALLINT" | "MEDIUMINT" | "INT" | "BIGINT"
+            | "YEAR" => text
+                .parse()
+                .map_or_else(|_| Value::Text(text.to_string()), Value::Int),
+            "FLOAT4" | "FLOAT8" | "FLOAT" | "DOUBLE" => text
+                .parse()
+                .map_or_else(|_| Value::Text(text.to_string()), Value::Float),
+            _ => Value::Text(text.to_string()),
+        }
+    }
+
+    /// 二进制类型的字段，非 UTF-8 的内容原样保留
+    #[cfg(feature = "mysql")]
+    pub(crate) fn from_bytes(bytes: &[u8], type_name: &str) -> Self {
+        match std::str::from_utf8(bytes) {
+            Ok(text) if !is_binary_type(type_name) => Value::from_text(text, type_name),
+            _ => Value::Bytes(bytes.to_vec()),
+        }
+    }
+}
+
+/// 是否为二进制字段类型
+#[cfg(feature = "mysql")]
+fn is_binary_type(type_name: &str) -> bool {
+    let type_name = type_name.to_uppercase();
+    type_name.ends_with("BLOB")
+        || type_name.ends_with("BINARY")
+        || type_name == "BYTEA"
+        || type_name == "BIT"
+}
+
+/// 表数据样本
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SampleRows {
+    // 字段名，与每行中值的顺序一致
+    pub columns: Vec<String>,
+    // 抽取到的行，最多 n 行
+    pub rows: Vec<Vec<Value>>,
+}
@@ -0,0 +1,106 @@
+use std::fs;
+use std::path::Path;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::error::MetaError;
+use crate::modal::Metadata;
+
+/// 当前的快照格式版本，格式发生不兼容变化时递增
+pub const FORMAT_VERSION: u32 = 1;
+
+/// 快照文件格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SnapshotFormat {
+    // 便于阅读和比对的 JSON
+    #[default]
+    Json,
+    // 体积更小的 MessagePack
+    MessagePack,
+}
+
+/// 写入磁盘的快照，在元数据外包一层版本信息
+#[derive(Debug, Serialize, Deserialize)]
+struct Snapshot {
+    // 快照格式版本
+    format_version: u32,
+    // 生成快照的 crate 版本，仅用于排查问题
+    #[serde(default)]
+    crate_version: String,
+    metadata: Metadata,
+}
+
+/// 只读取格式版本，缺失时说明是引入版本信息之前直接序列化的 Metadata
+#[derive(Deserialize)]
+struct VersionProbe {
+    format_version: Option<u32>,
+    #[serde(default)]
+    crate_version: String,
+}
+
+impl Metadata {
+    /// 将元数据保存为带版本信息的快照文件
+    pub fn save(&self, path: impl AsRef<Path>, format: SnapshotFormat) -> Result<(), MetaError> {
+        let snapshot = Snapshot {
+            format_version: FORMAT_VERSION,
+            crate_version: env!("CARGO_PKG_VERSION").to_string(),
+            metadata: self.clone(),
+        };
+        let bytes = match format {
+            SnapshotFormat::Json => serde_json::to_vec_pretty(&snapshot)
+                .map_err(|e| MetaError::BadRequest(format!("快照序列化失败: {e}")))?,
+            // 按字段名编码，新增或缺少的字段在读取时都能兼容
+            SnapshotFormat::MessagePack => rmp_serde::to_vec_named(&snapshot)
+                .map_err(|e| MetaError::BadRequest(format!("快照序列化失败: {e}")))?,
+        };
+        fs::write(path, bytes)?;
+        Ok(())
+    }
+
+    /// 读取快照文件，格式自动识别。
+    /// 旧版本缺少的字段取默认值，未知字段被忽略；格式版本高于当前版本时报错
+    pub fn load(path: impl AsRef<Path>) -> Result<Metadata, MetaError> {
+        let bytes = fs::read(path)?;
+        let is_json = bytes
+            .iter()
+            .find(|b| !b.is_ascii_whitespace())
+            .is_some_and(|b| *b == b'{');
+        let probe: VersionProbe = decode(&bytes, is_json)?;
+        match probe.format_version {
+            Some(version) if version > FORMAT_VERSION => Err(MetaError::BadRequest(format!(
+                "快照格式版本 {version} 高于当前支持的版本 {FORMAT_VERSION}（由 {} 生成）",
+                probe.crate_version
+            ))),
+            Some(_) => Ok(decode::<Snapshot>(&bytes, is_json)?.metadata),
+            None => decode(&bytes, is_json),
+        }
+    }
+
+    /// 以 YAML 文本输出元数据，便于在代码评审中逐行比对。
+    /// 字段按结构体定义的顺序输出，标签等映射按键排序；表和字段的顺序见 [`Metadata::sort`]
+    #[cfg(feature = "yaml")]
+    pub fn to_yaml(&self) -> Result<String, MetaError> {
+        serde_yaml::to_string(self)
+            .map_err(|e| MetaError::BadRequest(format!("YAML序列化失败: {e}")))
+    }
+
+    /// 以 TOML 文本输出元数据，字段顺序同 YAML 输出。
+    /// TOML 没有空值，为 None 的字段不输出
+    #[cfg(feature = "toml")]
+    pub fn to_toml(&self) -> Result<String, MetaError> {
+        toml::to_string_pretty(self)
+            .map_err(|e| MetaError::BadRequest(format!("TOML序列化失败: {e}")))
+    }
+}
+
+/// 按格式反序列化
+fn decode<T: DeserializeOwned>(bytes: &[u8], is_json: bool) -> Result<T, MetaError> {
+    if is_json {
+        serde_json::from_slice(bytes)
+            .map_err(|e| MetaError::BadRequest(format!("快照解析失败: {e}")))
+    } else {
+        rmp_serde::from_slice(bytes)
+            .map_err(|e| MetaError::BadRequest(format!("快照解析失败: {e}")))
+    }
+}
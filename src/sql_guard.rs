@@ -0,0 +1,117 @@
+use std::ops::ControlFlow;
+
+use sqlparser::ast::{Expr, Query, SetExpr, Statement, Visit, Visitor};
+use sqlparser::dialect::{MySqlDialect, PostgreSqlDialect, SQLiteDialect};
+use sqlparser::parser::Parser;
+
+use crate::error::MetaError;
+use crate::ident::Dialect;
+
+/// 只读模式下禁止调用的函数：有副作用、可读取服务器文件或可长时间占用连接
+const DENIED_FUNCTIONS: &[&str] = &[
+    "pg_sleep",
+    "pg_sleep_for",
+    "pg_sleep_until",
+    "pg_read_file",
+    "pg_read_binary_file",
+    "pg_ls_dir",
+    "pg_stat_file",
+    "pg_terminate_backend",
+    "pg_cancel_backend",
+    "pg_reload_conf",
+    "pg_rotate_logfile",
+    "pg_advisory_lock",
+    "pg_advisory_xact_lock",
+    "lo_import",
+    "lo_export",
+    "set_config",
+    "nextval",
+    "setval",
+    "dblink",
+    "dblink_exec",
+    "sleep",
+    "benchmark",
+    "load_file",
+    "get_lock",
+];
+
+/// 校验 SQL 是否为只读查询：必须是单条 SELECT，不能含写操作、`SELECT INTO`、
+/// 行锁子句或危险函数
+pub fn validate_read_only(sql: &str, dialect: Dialect) -> Result<(), MetaError> {
+    let statements = parse_sql(sql, dialect)?;
+    match statements.as_slice() {
+        [] => Err(MetaError::InvalidArgument("SQL不能为空".into())),
+        [statement] => match statement.visit(&mut ReadOnlyVisitor) {
+            ControlFlow::Break(reason) => Err(MetaError::ReadOnlyViolation(reason)),
+            ControlFlow::Continue(()) => Ok(()),
+        },
+        _ => Err(MetaError::ReadOnlyViolation("不允许执行多条语句".into())),
+    }
+}
+
+/// 按方言解析 SQL
+pub(crate) fn parse_sql(sql: &str, dialect: Dialect) -> Result<Vec<Statement>, MetaError> {
+    match dialect {
+        Dialect::Postgresql => Parser::parse_sql(&PostgreSqlDialect {}, sql),
+        Dialect::MySql => Parser::parse_sql(&MySqlDialect {}, sql),
+        Dialect::Sqlite => Parser::parse_sql(&SQLiteDialect {}, sql),
+    }
+    .map_err(|e| MetaError::InvalidArgument(format!("SQL解析失败: {e}")))
+}
+
+/// 遍历语法树，遇到非只读的节点时中断并给出原因
+struct ReadOnlyVisitor;
+
+impl Visitor for ReadOnlyVisitor {
+    type Break = String;
+
+    fn pre_visit_statement(&mut self, statement: &Statement) -> ControlFlow<String> {
+        match statement {
+            Statement::Query(_) => ControlFlow::Continue(()),
+            other => ControlFlow::Break(format!("仅允许SELECT语句: {}", first_keyword(other))),
+        }
+    }
+
+    fn pre_visit_query(&mut self, query: &Query) -> ControlFlow<String> {
+        if !query.locks.is_empty() {
+            return ControlFlow::Break("不允许加锁查询(FOR UPDATE/FOR SHARE)".into());
+        }
+        if has_select_into(&query.body) {
+            return ControlFlow::Break("不允许SELECT INTO".into());
+        }
+        ControlFlow::Continue(())
+    }
+
+    fn pre_visit_expr(&mut self, expr: &Expr) -> ControlFlow<String> {
+        if let Expr::Function(function) = expr
+            && let Some(name) = function.name.0.last()
+        {
+            let name = name.value.to_lowercase();
+            if DENIED_FUNCTIONS.contains(&name.as_str()) {
+                return ControlFlow::Break(format!("不允许调用函数: {name}"));
+            }
+        }
+        ControlFlow::Continue(())
+    }
+}
+
+/// 查询体（含集合运算的各分支）中是否有 `SELECT INTO`
+fn has_select_into(body: &SetExpr) -> bool {
+    match body {
+        SetExpr::Select(select) => select.into.is_some(),
+        SetExpr::SetOperation { left, right, .. } => {
+            has_select_into(left) || has_select_into(right)
+        }
+        _ => false,
+    }
+}
+
+/// 语句的首个关键字，用于错误提示
+fn first_keyword(statement: &Statement) -> String {
+    statement
+        .to_string()
+        .split_whitespace()
+        .next()
+        .unwrap_or_default()
+        .to_uppercase()
+}
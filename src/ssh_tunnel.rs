@@ -0,0 +1,172 @@
+use std::collections::VecDeque;
+use std::io::{BufRead, BufReader};
+use std::net::{Ipv4Addr, TcpListener, TcpStream};
+use std::path::PathBuf;
+use std::process::{Child, ChildStderr, Command, Stdio};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use derive_builder::Builder;
+
+use crate::error::MetaError;
+
+/// 经由跳板机访问数据库的 SSH 隧道配置。隧道由本机的 `ssh` 命令建立，
+/// 未指定私钥时使用 ssh-agent 或 `~/.ssh` 下的默认密钥，主机密钥按 `known_hosts` 校验
+#[derive(Debug, Clone, Builder)]
+pub struct SshTunnelConfig {
+    // 跳板机地址
+    pub host: String,
+    // 跳板机 SSH 端口
+    #[builder(default = "22")]
+    pub port: u16,
+    // 登录跳板机的用户
+    pub user: String,
+    // 私钥文件，为空时使用 ssh-agent 或默认密钥
+    #[builder(default)]
+    pub identity_file: Option<PathBuf>,
+    // 跳板机视角下的数据库地址，为空时使用 ConnConfig.url
+    #[builder(default)]
+    pub remote_host: Option<String>,
+    // 跳板机视角下的数据库端口，为空时使用 ConnConfig.port
+    #[builder(default)]
+    pub remote_port: Option<u16>,
+    // 等待隧道可用的最长时间
+    #[builder(default = "Duration::from_secs(10)")]
+    pub ready_timeout: Duration,
+}
+
+/// 报错时保留的 ssh 错误输出行数
+const STDERR_LINES: usize = 20;
+
+impl SshTunnelConfig {
+    /// 以 `-` 开头的主机或用户会被 ssh 当作选项解析（如 `-oProxyCommand=...`），直接拒绝
+    pub fn validate(&self) -> Result<(), MetaError> {
+        for (name, value) in [("跳板机地址", &self.host), ("跳板机用户", &self.user)] {
+            if value.is_empty() {
+                return Err(MetaError::InvalidArgument(format!("{name}不能为空")));
+            }
+            if value.starts_with('-') {
+                return Err(MetaError::InvalidArgument(format!("{name}不能以 - 开头: {value}")));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 正在运行的隧道，释放时结束 ssh 进程
+#[derive(Debug)]
+pub(crate) struct SshTunnel {
+    // ssh 子进程
+    child: Child,
+    // 本地转发端口
+    pub(crate) local_port: u16,
+    // 持续读取 ssh 错误输出的线程，避免管道写满后 ssh 阻塞；返回最后几行
+    stderr: Option<JoinHandle<VecDeque<String>>>,
+}
+
+impl SshTunnel {
+    /// 建立本地端口转发，直到本地端口可以连接
+    pub(crate) async fn open(
+        config: &SshTunnelConfig,
+        remote_host: &str,
+        remote_port: u16,
+    ) -> Result<Self, MetaError> {
+        config.validate()?;
+        // 先占用一个空闲端口再释放，交给 ssh 监听
+        let local_port = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))?
+            .local_addr()?
+            .port();
+
+        let mut command = Command::new("ssh");
+        command
+            .args([
+                "-N",
+                "-o",
+                "ExitOnForwardFailure=yes",
+                "-o",
+                "BatchMode=yes",
+            ])
+            .arg("-p")
+            .arg(config.port.to_string())
+            .arg("-L")
+            .arg(format!(
+                "127.0.0.1:{local_port}:{remote_host}:{remote_port}"
+            ));
+        if let Some(identity_file) = &config.identity_file {
+            command.arg("-i").arg(identity_file);
+        }
+        // `--` 之后的参数不再按选项解析
+        let mut child = command
+            .arg("--")
+            .arg(format!("{}@{}", config.user, config.host))
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| MetaError::ConnectionFailed(format!("启动 ssh 失败: {e}").into()))?;
+        let stderr = child.stderr.take().map(drain_stderr);
+
+        let mut tunnel = Self {
+            child,
+            local_port,
+            stderr,
+        };
+        tunnel.wait_ready(config.ready_timeout).await?;
+        Ok(tunnel)
+    }
+
+    /// ssh 进程是否仍在运行
+    pub(crate) fn is_alive(&mut self) -> bool {
+        matches!(self.child.try_wait(), Ok(None))
+    }
+
+    /// 轮询本地端口，ssh 提前退出时返回其错误输出
+    async fn wait_ready(&mut self, timeout: Duration) -> Result<(), MetaError> {
+        let started = Instant::now();
+        loop {
+            if let Some(status) = self.child.try_wait()? {
+                // ssh 退出后管道关闭，读取线程随即结束
+                let stderr = self
+                    .stderr
+                    .take()
+                    .and_then(|reader| reader.join().ok())
+                    .unwrap_or_default();
+                return Err(MetaError::ConnectionFailed(
+                    format!(
+                        "SSH 隧道建立失败({status}): {}",
+                        Vec::from(stderr).join("\n").trim()
+                    )
+                    .into(),
+                ));
+            }
+            if TcpStream::connect((Ipv4Addr::LOCALHOST, self.local_port)).is_ok() {
+                return Ok(());
+            }
+            if started.elapsed() > timeout {
+                return Err(MetaError::ConnectionFailed("等待 SSH 隧道超时".into()));
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+    }
+}
+
+impl Drop for SshTunnel {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// 在后台线程中读完 ssh 的错误输出，只保留最后 [`STDERR_LINES`] 行
+fn drain_stderr(pipe: ChildStderr) -> JoinHandle<VecDeque<String>> {
+    thread::spawn(move || {
+        let mut lines = VecDeque::with_capacity(STDERR_LINES);
+        for line in BufReader::new(pipe).lines().map_while(Result::ok) {
+            if lines.len() == STDERR_LINES {
+                lines.pop_front();
+            }
+            lines.push_back(line);
+        }
+        lines
+    })
+}
@@ -0,0 +1,158 @@
+use std::fmt::{self, Debug};
+use std::future::Future;
+use std::sync::{Arc, Mutex as StdMutex};
+use std::time::Duration;
+
+use derive_builder::Builder;
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+
+use crate::error::MetaError;
+use crate::meta::MetaTrait;
+
+/// 常驻模式选项
+#[derive(Debug, Clone, Builder)]
+#[builder(default)]
+pub struct SupervisorOptions {
+    // 建立连接失败后的最多重试次数，只重试连接失败，认证失败等错误直接返回
+    pub max_retries: u32,
+    // 第一次重试前的等待时间，之后每次翻倍
+    pub initial_backoff: Duration,
+    // 重试等待时间的上限
+    pub max_backoff: Duration,
+}
+
+impl Default for SupervisorOptions {
+    fn default() -> Self {
+        Self {
+            max_retries: 5,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+        }
+    }
+}
+
+/// 常驻模式下的连接状态
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ServiceStatus {
+    // 尚未建立连接；未开启常驻模式时每次调用都新建连接，始终为此状态
+    NotConnected,
+    // 连接池可用
+    Healthy,
+    // 连接已断开或重连失败，下次调用时重建连接池
+    Disconnected { last_error: String },
+    // 正在重建连接池，attempt 为第几次重试
+    Reconnecting { attempt: u32, last_error: String },
+}
+
+/// 在多次调用之间复用后端（及其连接池），连接断开时丢弃并按退避间隔重建
+#[derive(Clone)]
+pub(crate) struct Supervisor {
+    // 重试与退避设置
+    options: SupervisorOptions,
+    // 复用的后端，重建期间持有锁，并发调用等待同一次重建
+    handler: Arc<Mutex<Option<Arc<dyn MetaTrait>>>>,
+    // 当前状态
+    status: Arc<StdMutex<ServiceStatus>>,
+}
+
+impl Debug for Supervisor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Supervisor")
+            .field("options", &self.options)
+            .field("status", &self.status())
+            .finish()
+    }
+}
+
+impl Supervisor {
+    pub(crate) fn new(options: SupervisorOptions) -> Self {
+        Self {
+            options,
+            handler: Arc::default(),
+            status: Arc::new(StdMutex::new(ServiceStatus::NotConnected)),
+        }
+    }
+
+    /// 相同选项、互不共享连接的新实例，用于连接参数不同的服务副本
+    pub(crate) fn detached(&self) -> Self {
+        Self::new(self.options.clone())
+    }
+
+    pub(crate) fn status(&self) -> ServiceStatus {
+        self.status
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    fn set_status(&self, status: ServiceStatus) {
+        *self.status.lock().unwrap_or_else(|e| e.into_inner()) = status;
+    }
+
+    /// 返回复用的后端，没有时用 `connect` 建立；连接类错误按退避间隔重试
+    pub(crate) async fn handler<F, Fut>(&self, connect: F) -> Result<Arc<dyn MetaTrait>, MetaError>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = Result<Box<dyn MetaTrait>, MetaError>>,
+    {
+        let mut handler = self.handler.lock().await;
+        if let Some(handler) = handler.as_ref() {
+            return Ok(handler.clone());
+        }
+        let mut attempt = 0;
+        loop {
+            match connect().await {
+                Ok(connected) => {
+                    let connected: Arc<dyn MetaTrait> = Arc::from(connected);
+                    *handler = Some(connected.clone());
+                    self.set_status(ServiceStatus::Healthy);
+                    return Ok(connected);
+                }
+                Err(e) if is_connection_error(&e) && attempt < self.options.max_retries => {
+                    attempt += 1;
+                    let backoff = self.backoff(attempt);
+                    log::warn!("连接数据库失败，{backoff:?} 后第 {attempt} 次重试: {e}");
+                    self.set_status(ServiceStatus::Reconnecting {
+                        attempt,
+                        last_error: e.to_string(),
+                    });
+                    tokio::time::sleep(backoff).await;
+                }
+                Err(e) => {
+                    self.set_status(ServiceStatus::Disconnected {
+                        last_error: e.to_string(),
+                    });
+                    return Err(e);
+                }
+            }
+        }
+    }
+
+    /// 调用因连接问题失败时丢弃复用的后端，下次调用重建连接池
+    pub(crate) async fn observe(&self, error: &MetaError) {
+        if !is_connection_error(error) {
+            return;
+        }
+        if self.handler.lock().await.take().is_some() {
+            log::warn!("数据库连接已断开，下次调用时重建连接池: {error}");
+        }
+        self.set_status(ServiceStatus::Disconnected {
+            last_error: error.to_string(),
+        });
+    }
+
+    /// 第 `attempt` 次重试前的等待时间
+    fn backoff(&self, attempt: u32) -> Duration {
+        self.options
+            .initial_backoff
+            .saturating_mul(1 << (attempt - 1).min(16))
+            .min(self.options.max_backoff)
+    }
+}
+
+/// 服务重启、网络中断时的错误。获取连接超时（PoolExhausted）多半是并发过高，
+/// 连接池本身仍然可用，不视为断开
+fn is_connection_error(error: &MetaError) -> bool {
+    matches!(error, MetaError::ConnectionFailed(_))
+}
@@ -0,0 +1,80 @@
+use std::collections::BTreeMap;
+
+use crate::modal::{Column, Metadata};
+
+impl Metadata {
+    /// 从所有表和字段的注释中解析标签，写入各自的 `tags`，见 [`parse_tags`]
+    pub fn parse_comment_tags(&mut self) {
+        for table in &mut self.tables {
+            table.tags = table.comment.as_deref().map(parse_tags).unwrap_or_default();
+            tag_columns(&mut table.columns);
+        }
+        for view in &mut self.views {
+            tag_columns(&mut view.columns);
+        }
+    }
+}
+
+fn tag_columns(columns: &mut [Column]) {
+    for column in columns {
+        column.tags = column
+            .comment
+            .as_deref()
+            .map(parse_tags)
+            .unwrap_or_default();
+    }
+}
+
+/// 解析注释中的标签：`@pii`、`@owner=data-team` 和 `key=value` 三种写法，
+/// 没有取值的标签值为空串；取值含空白时用双引号括起，如 `desc="用户 手机号"`。
+/// 键以字母或下划线开头，由字母、数字、`_`、`-`、`.` 组成，因此 `1=正常` 这类说明文字不会被当作标签
+pub fn parse_tags(comment: &str) -> BTreeMap<String, String> {
+    let mut tags = BTreeMap::new();
+    let mut rest = comment.trim_start();
+    while !rest.is_empty() {
+        let (token, remaining) = next_token(rest);
+        rest = remaining.trim_start();
+
+        let (marked, token) = match token.strip_prefix('@') {
+            Some(token) => (true, token),
+            None => (false, token),
+        };
+        let (key, value) = match token.split_once('=') {
+            Some((key, value)) => (key, unquote(value)),
+            None if marked => (token, ""),
+            None => continue,
+        };
+        if is_key(key) {
+            tags.insert(key.to_string(), value.to_string());
+        }
+    }
+    tags
+}
+
+/// 取出下一个以空白分隔的片段，双引号内的空白不分隔
+fn next_token(text: &str) -> (&str, &str) {
+    let mut quoted = false;
+    for (index, c) in text.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            c if c.is_whitespace() && !quoted => return (&text[..index], &text[index..]),
+            _ => {}
+        }
+    }
+    (text, "")
+}
+
+fn unquote(value: &str) -> &str {
+    value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .unwrap_or(value)
+}
+
+fn is_key(key: &str) -> bool {
+    let mut chars = key.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+}
@@ -0,0 +1,228 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures::{Stream, StreamExt, stream};
+use serde::{Deserialize, Serialize};
+
+use crate::error::MetaError;
+use crate::ident::QualifiedName;
+use crate::meta::MetadataService;
+use crate::modal::{Column, IndexInfo, Metadata, TableInfo, ViewsInfo};
+
+/// 两次采集之间的结构变化
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum SchemaChangeEvent {
+    // 新增表
+    TableAdded(TableInfo),
+    // 删除表
+    TableDropped(QualifiedName),
+    // 新增视图
+    ViewAdded(ViewsInfo),
+    // 删除视图
+    ViewDropped(QualifiedName),
+    // 表或视图新增字段
+    ColumnAdded {
+        table: QualifiedName,
+        column: Column,
+    },
+    // 表或视图删除字段
+    ColumnDropped {
+        table: QualifiedName,
+        column: String,
+    },
+    // 字段的类型、长度、可空、默认值、主键或注释发生变化
+    ColumnChanged {
+        table: QualifiedName,
+        before: Column,
+        after: Column,
+    },
+    // 索引新增、删除或字段变化，新增时 before 为空，删除时 after 为空
+    IndexChanged {
+        table: QualifiedName,
+        index_name: String,
+        before: Vec<IndexInfo>,
+        after: Vec<IndexInfo>,
+    },
+}
+
+impl Metadata {
+    /// 比较两份元数据，返回从 `self` 到 `newer` 的结构变化。
+    /// 对象按 `schema.name` 匹配，遵循数据库的大小写规则
+    pub fn diff(&self, newer: &Metadata) -> Vec<SchemaChangeEvent> {
+        let mut events = Vec::new();
+
+        let old_tables = self.keyed(self.tables.iter().map(|t| (t.qualified_name(), t)));
+        let new_tables = self.keyed(newer.tables.iter().map(|t| (t.qualified_name(), t)));
+        for (key, (name, old)) in &old_tables {
+            match new_tables.get(key) {
+                Some((_, new)) => {
+                    self.diff_columns(name, &old.columns, &new.columns, &mut events);
+                    self.diff_indexes(name, &old.index_columns, &new.index_columns, &mut events);
+                }
+                None => events.push(SchemaChangeEvent::TableDropped(name.clone())),
+            }
+        }
+        for (key, (_, new)) in &new_tables {
+            if !old_tables.contains_key(key) {
+                events.push(SchemaChangeEvent::TableAdded((*new).clone()));
+            }
+        }
+
+        let old_views = self.keyed(self.views.iter().map(|v| (v.qualified_name(), v)));
+        let new_views = self.keyed(newer.views.iter().map(|v| (v.qualified_name(), v)));
+        for (key, (name, old)) in &old_views {
+            match new_views.get(key) {
+                Some((_, new)) => self.diff_columns(name, &old.columns, &new.columns, &mut events),
+                None => events.push(SchemaChangeEvent::ViewDropped(name.clone())),
+            }
+        }
+        for (key, (_, new)) in &new_views {
+            if !old_views.contains_key(key) {
+                events.push(SchemaChangeEvent::ViewAdded((*new).clone()));
+            }
+        }
+        events
+    }
+
+    /// 按规范化名称建立映射，不区分大小写时键为小写
+    fn keyed<'a, T>(
+        &self,
+        objects: impl Iterator<Item = (QualifiedName, &'a T)>,
+    ) -> BTreeMap<String, (QualifiedName, &'a T)> {
+        objects
+            .map(|(name, object)| (self.normalize(&name.to_string()), (name, object)))
+            .collect()
+    }
+
+    fn diff_columns(
+        &self,
+        table: &QualifiedName,
+        old: &[Column],
+        new: &[Column],
+        events: &mut Vec<SchemaChangeEvent>,
+    ) {
+        for before in old {
+            match new.iter().find(|c| self.ident_eq(&c.name, &before.name)) {
+                Some(after) if column_changed(before, after) => {
+                    events.push(SchemaChangeEvent::ColumnChanged {
+                        table: table.clone(),
+                        before: before.clone(),
+                        after: after.clone(),
+                    })
+                }
+                Some(_) => {}
+                None => events.push(SchemaChangeEvent::ColumnDropped {
+                    table: table.clone(),
+                    column: before.name.clone(),
+                }),
+            }
+        }
+        for after in new {
+            if !old.iter().any(|c| self.ident_eq(&c.name, &after.name)) {
+                events.push(SchemaChangeEvent::ColumnAdded {
+                    table: table.clone(),
+                    column: after.clone(),
+                });
+            }
+        }
+    }
+
+    fn diff_indexes(
+        &self,
+        table: &QualifiedName,
+        old: &[IndexInfo],
+        new: &[IndexInfo],
+        events: &mut Vec<SchemaChangeEvent>,
+    ) {
+        let group = |indexes: &[IndexInfo]| {
+            let mut grouped: BTreeMap<String, Vec<IndexInfo>> = BTreeMap::new();
+            for index in indexes {
+                grouped
+                    .entry(self.normalize(&index.index_name))
+                    .or_default()
+                    .push(index.clone());
+            }
+            grouped
+        };
+        let (old, new) = (group(old), group(new));
+        let keys: Vec<&String> = old
+            .keys()
+            .chain(new.keys().filter(|k| !old.contains_key(*k)))
+            .collect();
+        for key in keys {
+            let before = old.get(key).cloned().unwrap_or_default();
+            let after = new.get(key).cloned().unwrap_or_default();
+            if self.index_signature(&before) != self.index_signature(&after) {
+                let index_name = before
+                    .first()
+                    .or(after.first())
+                    .map(|i| i.index_name.clone())
+                    .unwrap_or_default();
+                events.push(SchemaChangeEvent::IndexChanged {
+                    table: table.clone(),
+                    index_name,
+                    before,
+                    after,
+                });
+            }
+        }
+    }
+
+    /// 索引的比较依据：按顺序的字段名和是否唯一
+    fn index_signature(&self, index: &[IndexInfo]) -> Vec<(String, bool)> {
+        index
+            .iter()
+            .map(|i| (self.normalize(&i.column_name), i.is_unique))
+            .collect()
+    }
+}
+
+/// 字段定义是否变化，默认值按分类比较
+fn column_changed(before: &Column, after: &Column) -> bool {
+    !before.type_name.eq_ignore_ascii_case(&after.type_name)
+        || before.length != after.length
+        || before.digit != after.digit
+        || before.is_nullable != after.is_nullable
+        || before.auto_increment.unwrap_or_default() != after.auto_increment.unwrap_or_default()
+        || before.is_pk != after.is_pk
+        || before.comment != after.comment
+        || before.default_value() != after.default_value()
+}
+
+impl MetadataService {
+    /// 按固定间隔重新采集元数据并与上一次结果比较，产出结构变化事件。
+    /// `filter` 决定哪些表/视图参与比较；首次采集只建立基线，不产出事件。
+    /// 某次采集失败时产出错误并保留原基线，下一个间隔继续
+    pub fn watch<'a, F>(
+        &'a self,
+        interval: Duration,
+        filter: F,
+    ) -> impl Stream<Item = Result<SchemaChangeEvent, MetaError>> + 'a
+    where
+        F: Fn(&QualifiedName) -> bool + Send + Sync + 'a,
+    {
+        let filter = Arc::new(filter);
+        stream::unfold((None::<Metadata>, false), move |(baseline, started)| {
+            let filter = filter.clone();
+            async move {
+                if started {
+                    tokio::time::sleep(interval).await;
+                }
+                match self.get_metadata().await {
+                    Ok(mut current) => {
+                        current.tables.retain(|t| filter(&t.qualified_name()));
+                        current.views.retain(|v| filter(&v.qualified_name()));
+                        let events = baseline.map(|b| b.diff(&current)).unwrap_or_default();
+                        Some((Ok(events), (Some(current), true)))
+                    }
+                    Err(e) => Some((Err(e), (baseline, true))),
+                }
+            }
+        })
+        .flat_map(|step| match step {
+            Ok(events) => stream::iter(events.into_iter().map(Ok)).left_stream(),
+            Err(e) => stream::once(async { Err(e) }).right_stream(),
+        })
+    }
+}